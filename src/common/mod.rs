@@ -1,5 +1,7 @@
 //! Common, shared types.
 
+pub mod ron_asset;
+pub mod rng;
 pub mod state;
 pub mod tunables;
 
@@ -0,0 +1,53 @@
+//! Small seeded RNG for gameplay rolls (loot, AI choices).
+//!
+//! xorshift64*: a few instructions per roll, no dependencies, and the same sequence on
+//! every platform. A run seeded the same way makes the same choices, so headless tests
+//! can assert exact outcomes. Not for anything security-related.
+
+/// Deterministic random stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    /// xorshift never leaves 0, so a zero seed is remapped.
+    pub fn new(seed: u64) -> Self {
+        Self(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// Uniform in [0, 1).
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        // 24 random bits -> float in [0,1)
+        let v = (self.next_u64() >> 40) as u32;
+        (v as f32) / ((1u32 << 24) as f32)
+    }
+
+    /// `true` with probability `p` (clamped to [0, 1]).
+    #[inline]
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p.clamp(0.0, 1.0)
+    }
+
+    /// Uniform index in `0..n` (`n` must be > 0).
+    #[inline]
+    pub fn index(&mut self, n: usize) -> usize {
+        debug_assert!(n > 0);
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+impl Default for SeededRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
@@ -0,0 +1,62 @@
+//! Generic RON asset loading for designer-authored data files.
+//!
+//! Every data-driven definition (weapons, patterns, ...) is a plain `serde` struct.
+//! Implementing `RonAsset` gives it a file extension; `register_ron_asset` wires up
+//! the asset storage + loader in one call, so adding a new data type is two lines.
+
+use std::marker::PhantomData;
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+
+/// An asset deserialized straight from a RON file.
+pub trait RonAsset: Asset + DeserializeOwned {
+    /// Full extensions without the leading dot (e.g. `"weapon.ron"`).
+    const EXTENSIONS: &'static [&'static str];
+
+    /// Parse from an in-memory string (tests, tooling).
+    fn from_ron_str(src: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_str(src)
+    }
+}
+
+#[derive(TypePath)]
+pub struct RonAssetLoader<A: RonAsset> {
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A: RonAsset> Default for RonAssetLoader<A> {
+    fn default() -> Self {
+        Self { _marker: PhantomData }
+    }
+}
+
+impl<A: RonAsset> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<A>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        A::EXTENSIONS
+    }
+}
+
+/// Register asset storage and the RON loader for `A`.
+///
+/// Requires `AssetPlugin` (present in both the full and headless configurations).
+pub fn register_ron_asset<A: RonAsset>(app: &mut App) {
+    app.init_asset::<A>()
+        .register_asset_loader(RonAssetLoader::<A>::default());
+}
@@ -20,3 +20,10 @@ where
     world.flush();
     out
 }
+
+/// Create a `Time<Fixed>` whose delta is `dt` seconds, for a single system run.
+pub fn fixed_time_with_delta(dt: f32) -> Time<Fixed> {
+    let mut t = Time::<Fixed>::default();
+    t.advance_by(std::time::Duration::from_secs_f32(dt));
+    t
+}
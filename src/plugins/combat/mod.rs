@@ -0,0 +1,265 @@
+//! Combat plugin: the central damage pipeline.
+//!
+//! # Data flow
+//! ```text
+//!   producers (bullet resolve, enemy fire, explosions, hitscan rays, ...)
+//!        │  DamageRequest { source, target, amount, damage_type, position, impulse, .. }
+//!        v
+//!   apply_damage  (FixedPostUpdate, after every producer)
+//!        - the ONLY writer of Armour / Health / Invulnerability / Knockback on hit
+//!        │  DamageEvent { .., amount dealt, was_armour_hit, armour_broken, was_kill }
+//!        v
+//!   subscribers: armour FX, death trigger ordering, score, audio, analytics
+//! ```
+//!
+//! # Why requests *and* events?
+//! Producers only know what they *tried* to do (a bullet touched an enemy). Whether that
+//! became damage depends on armour, i-frames and whether the target is already dead.
+//! Keeping those rules in one system means every damage source follows them, and
+//! subscribers read the outcome instead of diffing component values between ticks.
+//!
+//! # Rules (applied per request, in order)
+//! 1. Targets without `Health`, or already at 0 HP, are ignored (no double kills).
+//! 2. Active i-frames (`Invulnerability`) swallow the request entirely.
+//! 3. Look up the target's `Resistance` for the request's `DamageType` (neutral if the
+//!    target has no `Resistances`).
+//! 4. Armour gate: armour up, and neither the shot nor the resistance bypasses armour
+//!    => wear `armour_wear` hits, deal 0.
+//! 5. Otherwise subtract `amount × damage_mult` from `Health`; targets with i-frames get
+//!    a fresh window.
+//! 6. The impulse is applied to `Knockback` (scaled by `Weight`) whenever 2. passed.
+//! 7. A carried `StatusApply` lands on `StatusEffects` unless armour blocked the hit.
+//!
+//! Periodic requests (status ticks, see `status`) skip 2. and never grant i-frames:
+//! a burn must not make its target immune to bullets, or to its own next tick.
+//!
+//! # Resistances
+//! `Resistances` is plain serde data so archetype files can carry it. The defaults encode
+//! the baseline matchup: kinetic is stopped by armour one hit at a time, energy strips
+//! armour twice as fast, and nothing is resisted or amplified.
+
+pub mod status;
+
+use bevy::ecs::message::Messages;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::common::state::GameState;
+use crate::plugins::projectiles::collision::{
+    process_enemy_bullet_collisions, process_player_bullet_collisions,
+};
+use crate::plugins::projectiles::components::{Armour, Health, Invulnerability, Knockback, Weight};
+use crate::plugins::projectiles::explosion::apply_explosions;
+use crate::plugins::projectiles::hitscan::resolve_hitscan;
+
+use status::{tick_status_effects, StatusApply, StatusEffects};
+
+/// What kind of damage a hit deals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Kinetic,
+    Fire,
+    Energy,
+    Explosive,
+    Poison,
+}
+
+/// How one damage type interacts with a target.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Resistance {
+    /// Health damage multiplier: < 1 resists, > 1 is a weakness, 0 is immune.
+    pub damage_mult: f32,
+    /// Armour hits worn by one blocked hit of this type.
+    pub armour_wear: u16,
+    /// Damage of this type goes straight to `Health`.
+    pub ignores_armour: bool,
+}
+
+impl Default for Resistance {
+    fn default() -> Self {
+        Self { damage_mult: 1.0, armour_wear: 1, ignores_armour: false }
+    }
+}
+
+impl Resistance {
+    /// Scale a raw amount; rounds to the nearest point, never below 0.
+    #[inline]
+    pub fn scale(&self, amount: i32) -> i32 {
+        ((amount as f32 * self.damage_mult).round() as i32).max(0)
+    }
+}
+
+/// Per-target resistance table, one entry per `DamageType`.
+///
+/// Missing entries in a data file fall back to the defaults below.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub kinetic: Resistance,
+    pub fire: Resistance,
+    pub energy: Resistance,
+    pub explosive: Resistance,
+    pub poison: Resistance,
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Self {
+            kinetic: Resistance::default(),
+            fire: Resistance::default(),
+            energy: Resistance { armour_wear: 2, ..default() },
+            explosive: Resistance::default(),
+            poison: Resistance::default(),
+        }
+    }
+}
+
+impl Resistances {
+    #[inline]
+    pub fn get(&self, damage_type: DamageType) -> Resistance {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Fire => self.fire,
+            DamageType::Energy => self.energy,
+            DamageType::Explosive => self.explosive,
+            DamageType::Poison => self.poison,
+        }
+    }
+}
+
+/// Intent: "`source` tries to deal `amount` to `target`".
+#[derive(Message, Clone, Copy, Debug)]
+pub struct DamageRequest {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    pub amount: i32,
+    pub damage_type: DamageType,
+    /// World position of the hit (impact point or blast centre).
+    pub position: Vec2,
+    /// Knockback impulse in px/s at weight 1.
+    pub impulse: Vec2,
+    pub armour_piercing: bool,
+    /// Status effect applied on a hit that armour did not block.
+    pub status: Option<StatusApply>,
+    /// Damage-over-time tick: ignores i-frames and does not grant them.
+    pub periodic: bool,
+}
+
+/// Outcome of one applied `DamageRequest`.
+///
+/// Written for every request that got past the i-frame check, including fully
+/// armour-blocked hits (`amount == 0`, `was_armour_hit == true`).
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct DamageEvent {
+    pub source: Option<Entity>,
+    pub target: Entity,
+    /// Health actually removed.
+    pub amount: i32,
+    pub damage_type: DamageType,
+    pub position: Vec2,
+    pub impulse: Vec2,
+    /// The hit was absorbed by armour (one armour hit worn).
+    pub was_armour_hit: bool,
+    /// This hit wore the last armour hit away.
+    pub armour_broken: bool,
+    /// This hit took the target from positive to non-positive HP.
+    pub was_kill: bool,
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Messages<DamageRequest>>()
+        .init_resource::<Messages<DamageEvent>>()
+        .add_systems(PostUpdate, update_damage_messages)
+        .add_systems(
+            FixedPostUpdate,
+            (
+                tick_status_effects,
+                apply_damage
+                    .after(tick_status_effects)
+                    .after(process_player_bullet_collisions)
+                    .after(process_enemy_bullet_collisions)
+                    .after(apply_explosions)
+                    .after(resolve_hitscan),
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+}
+
+/// Maintain damage message buffers (written and read within the fixed step).
+fn update_damage_messages(
+    mut requests: ResMut<Messages<DamageRequest>>,
+    mut events: ResMut<Messages<DamageEvent>>,
+) {
+    requests.update();
+    events.update();
+}
+
+/// Anything with `Health`, plus whichever damage rules apply to it.
+type DamageTargets<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Health,
+        Option<&'static mut Armour>,
+        Option<&'static mut Invulnerability>,
+        Option<(&'static mut Knockback, &'static Weight)>,
+        Option<&'static Resistances>,
+        Option<&'static mut StatusEffects>,
+    ),
+>;
+
+pub fn apply_damage(
+    mut requests: MessageReader<DamageRequest>,
+    mut events: MessageWriter<DamageEvent>,
+    mut q_targets: DamageTargets,
+) {
+    for req in requests.read() {
+        let Ok((mut hp, armour, iframes, shove, resistances, status)) = q_targets.get_mut(req.target) else { continue; };
+        if hp.hp <= 0 { continue; }
+        if !req.periodic && iframes.as_ref().is_some_and(|i| i.is_active()) { continue; }
+
+        let resistance = resistances.map_or_else(Resistance::default, |r| r.get(req.damage_type));
+        let bypass = req.armour_piercing || resistance.ignores_armour;
+
+        let mut was_armour_hit = false;
+        let mut armour_broken = false;
+        if let Some(mut armour) = armour.filter(|a| !bypass && a.is_up()) {
+            armour.wear(resistance.armour_wear.max(1));
+            was_armour_hit = true;
+            armour_broken = !armour.is_up();
+        }
+
+        let amount = if was_armour_hit { 0 } else { resistance.scale(req.amount) };
+        if amount > 0 {
+            hp.hp -= amount;
+            if let Some(mut iframes) = iframes.filter(|_| !req.periodic) {
+                iframes.grant(Invulnerability::DEFAULT_WINDOW_SECS);
+            }
+        }
+
+        if let Some((mut knockback, weight)) = shove {
+            knockback.apply(req.impulse, *weight);
+        }
+
+        if let (Some(apply), Some(mut status), false) = (req.status, status, was_armour_hit) {
+            status.apply(&apply);
+        }
+
+        events.write(DamageEvent {
+            source: req.source,
+            target: req.target,
+            amount,
+            damage_type: req.damage_type,
+            position: req.position,
+            impulse: req.impulse,
+            was_armour_hit,
+            armour_broken,
+            was_kill: amount > 0 && hp.hp <= 0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,192 @@
+//! Status effects: burn, poison, slow, freeze.
+//!
+//! # Data flow
+//! ```text
+//!   DamageRequest { status: Some(StatusApply), .. } ──(apply_damage)──> StatusEffects::apply
+//!   tick_status_effects (fixed step, before apply_damage)
+//!       - counts durations down on fixed (virtual-driven) time
+//!       - burn / poison ──> periodic DamageRequest (Fire / Poison) ──> Health
+//!   movement systems read StatusEffects::speed_mult()
+//! ```
+//!
+//! `StatusEffects` is always present on anything that can be afflicted: applying or
+//! expiring an effect only writes numbers, there is no archetype churn. Because the
+//! fixed clock follows virtual time, hitstop and slowmo pause effects too.
+//!
+//! # Stacking rules
+//! - Burn: refreshes. Duration and damage take the max of current and new.
+//! - Poison: stacks intensity up to `MAX_POISON_STACKS`; each application refreshes the
+//!   duration. Damage per second = potency × stacks.
+//! - Slow: strongest wins. Duration takes the max; potency is the fraction of speed removed.
+//! - Freeze: refreshes. Speed is 0 while active (wins over slow).
+//!
+//! Tick damage is accumulated fractionally, so low damage-per-second values still land
+//! exactly once per whole point instead of rounding away.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{DamageRequest, DamageType};
+
+pub const MAX_POISON_STACKS: u8 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum StatusKind {
+    Burn,
+    Poison,
+    Slow,
+    Freeze,
+}
+
+/// One application of a status effect (carried by bullets and damage requests).
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct StatusApply {
+    pub kind: StatusKind,
+    /// Seconds of virtual time.
+    pub duration_secs: f32,
+    /// Burn/poison: damage per second (per stack). Slow: fraction of speed removed.
+    /// Freeze: unused.
+    #[serde(default)]
+    pub potency: f32,
+}
+
+/// State of one effect slot.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Effect {
+    pub remaining: f32,
+    pub potency: f32,
+    pub stacks: u8,
+    /// Fractional tick damage not yet dealt.
+    pub carry: f32,
+}
+
+impl Effect {
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    #[inline]
+    fn refresh(&mut self, apply: &StatusApply) {
+        self.remaining = self.remaining.max(apply.duration_secs);
+        self.potency = self.potency.max(apply.potency);
+        self.stacks = 1;
+    }
+
+    /// Advance by `dt`; returns whole damage points due this step (damage-over-time slots).
+    fn tick_dot(&mut self, dt: f32) -> i32 {
+        if !self.is_active() { return 0; }
+
+        let active_dt = dt.min(self.remaining);
+        self.carry += self.potency * self.stacks as f32 * active_dt;
+        self.remaining = (self.remaining - dt).max(0.0);
+
+        let due = self.carry.floor();
+        self.carry -= due;
+        if !self.is_active() {
+            *self = Self::default();
+        }
+        due as i32
+    }
+
+    #[inline]
+    fn tick(&mut self, dt: f32) {
+        self.remaining = (self.remaining - dt).max(0.0);
+        if !self.is_active() {
+            *self = Self::default();
+        }
+    }
+}
+
+/// All status effects on one entity.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct StatusEffects {
+    pub burn: Effect,
+    pub poison: Effect,
+    pub slow: Effect,
+    pub freeze: Effect,
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, apply: &StatusApply) {
+        match apply.kind {
+            StatusKind::Burn => self.burn.refresh(apply),
+            StatusKind::Slow => self.slow.refresh(apply),
+            StatusKind::Freeze => self.freeze.refresh(apply),
+            StatusKind::Poison => {
+                let p = &mut self.poison;
+                p.remaining = apply.duration_secs.max(p.remaining);
+                p.potency = p.potency.max(apply.potency);
+                p.stacks = (p.stacks + 1).min(MAX_POISON_STACKS);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn has(&self, kind: StatusKind) -> bool {
+        match kind {
+            StatusKind::Burn => self.burn.is_active(),
+            StatusKind::Poison => self.poison.is_active(),
+            StatusKind::Slow => self.slow.is_active(),
+            StatusKind::Freeze => self.freeze.is_active(),
+        }
+    }
+
+    /// Multiplier for desired movement speed: 0 while frozen, reduced while slowed.
+    #[inline]
+    pub fn speed_mult(&self) -> f32 {
+        if self.freeze.is_active() {
+            0.0
+        } else if self.slow.is_active() {
+            (1.0 - self.slow.potency).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Advance all effects by `dt`; returns tick damage due as (burn, poison).
+    pub fn tick(&mut self, dt: f32) -> (i32, i32) {
+        self.slow.tick(dt);
+        self.freeze.tick(dt);
+        (self.burn.tick_dot(dt), self.poison.tick_dot(dt))
+    }
+}
+
+/// Periodic (damage-over-time) request; skips the armour gate and i-frames.
+#[inline]
+fn tick_request(target: Entity, pos: Vec2, amount: i32, damage_type: DamageType) -> DamageRequest {
+    DamageRequest {
+        source: None,
+        target,
+        amount,
+        damage_type,
+        position: pos,
+        impulse: Vec2::ZERO,
+        armour_piercing: true,
+        status: None,
+        periodic: true,
+    }
+}
+
+pub fn tick_status_effects(
+    time: Res<Time<Fixed>>,
+    mut q: Query<(Entity, &mut StatusEffects, Option<&Position>)>,
+    mut damage: MessageWriter<DamageRequest>,
+) {
+    let dt = time.delta_secs();
+
+    for (e, mut status, pos) in &mut q {
+        if *status == StatusEffects::default() { continue; }
+
+        let pos = pos.map_or(Vec2::ZERO, |p| p.0);
+        let (burn, poison) = status.tick(dt);
+
+        if burn > 0 {
+            damage.write(tick_request(e, pos, burn, DamageType::Fire));
+        }
+        if poison > 0 {
+            damage.write(tick_request(e, pos, poison, DamageType::Poison));
+        }
+    }
+}
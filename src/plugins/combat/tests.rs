@@ -0,0 +1,286 @@
+//! Unit tests for the damage pipeline.
+//!
+//! Requests are written by hand, so each rule is checked without any producer.
+
+use bevy::ecs::message::Messages;
+use bevy::prelude::*;
+
+use crate::common::test_utils::{fixed_time_with_delta, run_system_once};
+use crate::plugins::projectiles::components::{Armour, Health, Invulnerability, Knockback, Weight};
+
+use super::status::*;
+use super::*;
+
+fn damage_world() -> World {
+    let mut world = World::new();
+    world.init_resource::<Messages<DamageRequest>>();
+    world.init_resource::<Messages<DamageEvent>>();
+    world
+}
+
+fn hit(target: Entity, amount: i32) -> DamageRequest {
+    DamageRequest {
+        source: None,
+        target,
+        amount,
+        damage_type: DamageType::Kinetic,
+        position: Vec2::ZERO,
+        impulse: Vec2::ZERO,
+        armour_piercing: false,
+        status: None,
+        periodic: false,
+    }
+}
+
+fn apply(world: &mut World, reqs: impl IntoIterator<Item = DamageRequest>) -> Vec<DamageEvent> {
+    world.resource_mut::<Messages<DamageRequest>>().write_batch(reqs);
+    run_system_once(world, apply_damage);
+    world.resource_mut::<Messages<DamageRequest>>().clear();
+
+    let events = world.resource::<Messages<DamageEvent>>().iter_current_update_messages().copied().collect();
+    world.resource_mut::<Messages<DamageEvent>>().clear();
+    events
+}
+
+#[test]
+fn armour_absorbs_hits_until_broken_then_health_takes_damage() {
+    let mut world = damage_world();
+    let e = world
+        .spawn((Health { hp: 3 }, Armour { hits_remaining: 2, max_hits: 2 }))
+        .id();
+
+    let events = apply(&mut world, [hit(e, 1), hit(e, 1), hit(e, 2)]);
+
+    assert_eq!(events.len(), 3);
+    assert!(events[0].was_armour_hit && !events[0].armour_broken && events[0].amount == 0);
+    assert!(events[1].was_armour_hit && events[1].armour_broken);
+    assert!(!events[2].was_armour_hit && events[2].amount == 2 && !events[2].was_kill);
+    assert_eq!(world.get::<Health>(e).unwrap().hp, 1);
+}
+
+#[test]
+fn armour_piercing_skips_the_gate() {
+    let mut world = damage_world();
+    let e = world
+        .spawn((Health { hp: 3 }, Armour { hits_remaining: 2, max_hits: 2 }))
+        .id();
+
+    let events = apply(&mut world, [DamageRequest { armour_piercing: true, ..hit(e, 1) }]);
+
+    assert_eq!(events[0].amount, 1);
+    assert!(!events[0].was_armour_hit);
+    assert_eq!(world.get::<Armour>(e).unwrap().hits_remaining, 2);
+}
+
+#[test]
+fn kill_is_reported_once_and_dead_targets_ignore_damage() {
+    let mut world = damage_world();
+    let e = world.spawn(Health { hp: 2 }).id();
+
+    let events = apply(&mut world, [hit(e, 5), hit(e, 5)]);
+
+    assert_eq!(events.len(), 1);
+    assert!(events[0].was_kill);
+    assert_eq!(world.get::<Health>(e).unwrap().hp, -3);
+}
+
+#[test]
+fn iframes_swallow_requests_and_are_granted_on_damage() {
+    let mut world = damage_world();
+    let e = world
+        .spawn((Health { hp: 10 }, Invulnerability::default(), Knockback::default(), Weight(1.0)))
+        .id();
+    let shove = DamageRequest { impulse: Vec2::X * 100.0, ..hit(e, 2) };
+
+    let events = apply(&mut world, [shove, shove]);
+
+    assert_eq!(events.len(), 1, "second hit lands inside the fresh i-frame window");
+    assert_eq!(world.get::<Health>(e).unwrap().hp, 8);
+    assert!(world.get::<Invulnerability>(e).unwrap().is_active());
+    assert_eq!(world.get::<Knockback>(e).unwrap().velocity, Vec2::X * 100.0);
+}
+
+#[test]
+fn targets_without_health_are_ignored() {
+    let mut world = damage_world();
+    let e = world.spawn_empty().id();
+
+    assert!(apply(&mut world, [hit(e, 1)]).is_empty());
+}
+
+// -----------------------------------------------------------------------------
+// Damage types + resistances
+// -----------------------------------------------------------------------------
+
+fn armoured(world: &mut World, resistances: Resistances) -> Entity {
+    world
+        .spawn((Health { hp: 10 }, Armour { hits_remaining: 4, max_hits: 4 }, resistances))
+        .id()
+}
+
+#[test]
+fn energy_strips_armour_twice_as_fast_as_kinetic() {
+    let mut world = damage_world();
+    let kinetic = armoured(&mut world, Resistances::default());
+    let energy = armoured(&mut world, Resistances::default());
+
+    apply(&mut world, [hit(kinetic, 1), DamageRequest { damage_type: DamageType::Energy, ..hit(energy, 1) }]);
+
+    assert_eq!(world.get::<Armour>(kinetic).unwrap().hits_remaining, 3);
+    assert_eq!(world.get::<Armour>(energy).unwrap().hits_remaining, 2);
+    assert_eq!(world.get::<Health>(kinetic).unwrap().hp, 10);
+    assert_eq!(world.get::<Health>(energy).unwrap().hp, 10);
+}
+
+#[test]
+fn resistances_scale_health_damage_and_can_bypass_armour() {
+    let mut world = damage_world();
+    let table = Resistances {
+        fire: Resistance { damage_mult: 2.0, ignores_armour: true, ..default() },
+        poison: Resistance { damage_mult: 0.0, ignores_armour: true, ..default() },
+        ..default()
+    };
+    let e = armoured(&mut world, table);
+
+    let events = apply(
+        &mut world,
+        [
+            DamageRequest { damage_type: DamageType::Fire, ..hit(e, 2) },
+            DamageRequest { damage_type: DamageType::Poison, ..hit(e, 5) },
+        ],
+    );
+
+    assert_eq!(events[0].amount, 4);
+    assert!(!events[0].was_armour_hit);
+    assert_eq!(events[1].amount, 0, "immune");
+    assert_eq!(world.get::<Health>(e).unwrap().hp, 6);
+    assert_eq!(world.get::<Armour>(e).unwrap().hits_remaining, 4);
+}
+
+#[test]
+fn resistances_parse_from_partial_ron() {
+    let table: Resistances = ron::de::from_str("(kinetic: (damage_mult: 0.5), energy: (armour_wear: 3))")
+        .expect("valid resistance RON");
+
+    assert_eq!(table.kinetic.damage_mult, 0.5);
+    assert_eq!(table.kinetic.armour_wear, 1);
+    assert_eq!(table.energy.armour_wear, 3);
+    assert_eq!(table.fire, Resistance::default());
+    assert_eq!(table.get(DamageType::Energy).armour_wear, 3);
+}
+
+fn burn(duration_secs: f32, potency: f32) -> StatusApply {
+    StatusApply { kind: StatusKind::Burn, duration_secs, potency }
+}
+
+fn poison(duration_secs: f32, potency: f32) -> StatusApply {
+    StatusApply { kind: StatusKind::Poison, duration_secs, potency }
+}
+
+/// Run one `tick_status_effects` step of `dt` seconds and return the requests it wrote.
+fn tick(world: &mut World, dt: f32) -> Vec<DamageRequest> {
+    world.insert_resource(fixed_time_with_delta(dt));
+
+    run_system_once(world, tick_status_effects);
+    let reqs = world.resource::<Messages<DamageRequest>>().iter_current_update_messages().copied().collect();
+    world.resource_mut::<Messages<DamageRequest>>().clear();
+    reqs
+}
+
+#[test]
+fn status_stacking_rules() {
+    let mut status = StatusEffects::default();
+
+    // Burn refreshes: longest duration and strongest potency win, no stacks.
+    status.apply(&burn(2.0, 1.0));
+    status.apply(&burn(1.0, 3.0));
+    assert_eq!((status.burn.remaining, status.burn.potency, status.burn.stacks), (2.0, 3.0, 1));
+
+    // Poison stacks up to the cap and refreshes its duration.
+    for _ in 0..7 {
+        status.apply(&poison(3.0, 1.0));
+    }
+    assert_eq!(status.poison.stacks, MAX_POISON_STACKS);
+    assert_eq!(status.poison.remaining, 3.0);
+
+    // Strongest slow wins; freeze overrides it.
+    status.apply(&StatusApply { kind: StatusKind::Slow, duration_secs: 1.0, potency: 0.5 });
+    status.apply(&StatusApply { kind: StatusKind::Slow, duration_secs: 1.0, potency: 0.25 });
+    assert_eq!(status.speed_mult(), 0.5);
+    status.apply(&StatusApply { kind: StatusKind::Freeze, duration_secs: 0.5, potency: 0.0 });
+    assert_eq!(status.speed_mult(), 0.0);
+
+    // Freeze thaws first, then the slow runs out.
+    status.tick(0.5);
+    assert!(!status.has(StatusKind::Freeze));
+    assert_eq!(status.speed_mult(), 0.5);
+    status.tick(0.5);
+    assert_eq!(status.speed_mult(), 1.0);
+}
+
+#[test]
+fn burn_ticks_accumulate_fractional_damage_and_expire() {
+    let mut world = damage_world();
+    let e = world.spawn((Health { hp: 10 }, StatusEffects::default())).id();
+    world.get_mut::<StatusEffects>(e).unwrap().apply(&burn(1.0, 2.0));
+
+    // 2 dps over 0.25 s steps: a point every other step.
+    let dealt: Vec<i32> = (0..6).map(|_| tick(&mut world, 0.25).iter().map(|r| r.amount).sum()).collect();
+    assert_eq!(dealt, [0, 1, 0, 1, 0, 0]);
+    assert_eq!(*world.get::<StatusEffects>(e).unwrap(), StatusEffects::default());
+}
+
+#[test]
+fn poison_damage_scales_with_stacks() {
+    let mut world = damage_world();
+    let e = world.spawn((Health { hp: 10 }, StatusEffects::default())).id();
+    {
+        let mut status = world.get_mut::<StatusEffects>(e).unwrap();
+        status.apply(&poison(2.0, 1.0));
+        status.apply(&poison(2.0, 1.0));
+    }
+
+    let reqs = tick(&mut world, 1.0);
+    assert_eq!(reqs.len(), 1);
+    assert_eq!((reqs[0].amount, reqs[0].damage_type), (2, DamageType::Poison));
+    assert!(reqs[0].periodic && reqs[0].armour_piercing);
+}
+
+#[test]
+fn periodic_damage_ignores_iframes_and_grants_none() {
+    let mut world = damage_world();
+    let e = world
+        .spawn((Health { hp: 5 }, Invulnerability { remaining: 0.5 }, StatusEffects::default()))
+        .id();
+    world.get_mut::<StatusEffects>(e).unwrap().apply(&burn(1.0, 1.0));
+
+    let reqs = tick(&mut world, 1.0);
+    let events = apply(&mut world, reqs);
+
+    assert_eq!(events[0].amount, 1);
+    assert_eq!(world.get::<Health>(e).unwrap().hp, 4);
+    assert_eq!(world.get::<Invulnerability>(e).unwrap().remaining, 0.5);
+}
+
+#[test]
+fn status_lands_only_when_armour_does_not_block() {
+    let mut world = damage_world();
+    let e = world
+        .spawn((Health { hp: 5 }, Armour { hits_remaining: 1, max_hits: 1 }, StatusEffects::default()))
+        .id();
+    let burning = DamageRequest { status: Some(burn(1.0, 1.0)), ..hit(e, 1) };
+
+    apply(&mut world, [burning]);
+    assert!(!world.get::<StatusEffects>(e).unwrap().has(StatusKind::Burn));
+
+    apply(&mut world, [burning]);
+    assert!(world.get::<StatusEffects>(e).unwrap().has(StatusKind::Burn));
+}
+
+#[test]
+fn status_apply_parses_from_ron() {
+    let apply: StatusApply = ron::de::from_str("(kind: Slow, duration_secs: 1.5, potency: 0.4)")
+        .expect("valid status RON");
+
+    assert_eq!(apply, StatusApply { kind: StatusKind::Slow, duration_secs: 1.5, potency: 0.4 });
+}
@@ -0,0 +1,360 @@
+//! Data-driven enemy archetypes.
+//!
+//! # Data flow
+//! ```text
+//!   assets/enemies/<id>.enemy.ron ──(RonAssetLoader)──> Assets<EnemyArchetype>
+//!   resolve_enemy_archetypes: loaded definitions ──> EnemyArchetypes (id -> Archetype,
+//!                                                    emitter pattern and behaviour tree
+//!                                                    handles resolved)
+//!   spawn_enemy(commands, &EnemyArchetypes, id, pos) ──> enemy entity
+//! ```
+//!
+//! The id is the file stem (`gunner` for `enemies/gunner.enemy.ron`). Wave scripts and
+//! any other spawner only name ids; everything about the enemy lives in its file.
+//!
+//! `EnemyArchetypes` copies each definition once it has loaded, so spawning reads plain
+//! data and never touches `Assets<EnemyArchetype>`. Tests (and tools) fill it straight
+//! from a string with `EnemyArchetypes::insert`.
+//!
+//! # Loot
+//! Each `LootDrop` rolls once on death against the seeded `LootRng`: with the same seed
+//! and the same kills, a run drops the same loot.
+
+use std::collections::HashMap;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::state::state_scoped::DespawnOnExit;
+use bevy_firefly::prelude::Occluder2d;
+use serde::Deserialize;
+
+use crate::common::ron_asset::RonAsset;
+use crate::common::rng::SeededRng;
+use crate::common::state::GameState;
+use crate::plugins::combat::status::StatusEffects;
+use crate::plugins::combat::Resistances;
+use crate::plugins::projectiles::cancel::score_pickup_bundle;
+use crate::plugins::projectiles::components::{Armour, Enemy, Health, Knockback, Weight};
+use crate::plugins::projectiles::emitter::{BulletPattern, Emitter};
+use crate::plugins::projectiles::layers::Layer;
+
+use super::behaviour::{BehaviourTree, Brain};
+use super::crowd::Separation;
+use super::movement::{Flee, Movement, MovementClock, NavPath};
+use super::perception::{Awareness, Perception};
+use super::{ArmourFx, EnemyLifeState};
+
+/// Archetypes loaded at startup (file stems under `assets/enemies/`).
+pub const ARCHETYPE_IDS: &[&str] = &["target", "gunner", "orbs", "spiral", "wave", "swarmer", "skirmisher"];
+
+/// Seed for loot rolls on entering `InGame`.
+pub const LOOT_SEED: u64 = 0x5EED_1007;
+
+/// Collider (and default sprite) shape.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum EnemyShape {
+    Circle { radius: f32 },
+    Box { width: f32, height: f32 },
+    Capsule { radius: f32, length: f32 },
+}
+
+impl EnemyShape {
+    pub fn collider(&self) -> Collider {
+        match *self {
+            EnemyShape::Circle { radius } => Collider::circle(radius),
+            EnemyShape::Box { width, height } => Collider::rectangle(width, height),
+            EnemyShape::Capsule { radius, length } => Collider::capsule(radius, length),
+        }
+    }
+
+    /// Bounding size, used for the sprite when the file doesn't give one.
+    pub fn size(&self) -> Vec2 {
+        match *self {
+            EnemyShape::Circle { radius } => Vec2::splat(radius * 2.0),
+            EnemyShape::Box { width, height } => Vec2::new(width, height),
+            EnemyShape::Capsule { radius, length } => Vec2::new(radius * 2.0, length + radius * 2.0),
+        }
+    }
+
+    /// Radius of the circle enclosing the shape (occluders, spacing).
+    pub fn bounding_radius(&self) -> f32 {
+        match *self {
+            EnemyShape::Circle { radius } => radius,
+            EnemyShape::Box { width, height } => Vec2::new(width, height).length() * 0.5,
+            EnemyShape::Capsule { radius, length } => radius + length * 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SpriteDef {
+    /// Body colour (sRGB) once armour is gone; armour up is always drawn armour-blue.
+    pub color: (f32, f32, f32),
+    /// `None` = the shape's bounding size.
+    pub size: Option<(f32, f32)>,
+}
+
+impl Default for SpriteDef {
+    fn default() -> Self {
+        Self { color: (0.9, 0.25, 0.25), size: None }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct EmitterDef {
+    /// Bullet pattern asset path.
+    pub pattern: String,
+    /// Seconds after spawning before the first volley.
+    pub warmup_secs: f32,
+}
+
+impl Default for EmitterDef {
+    fn default() -> Self {
+        Self { pattern: String::new(), warmup_secs: 1.0 }
+    }
+}
+
+/// One roll of the loot table: `count` score pickups worth `value` each, with `chance`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LootDrop {
+    pub chance: f32,
+    pub value: u32,
+    pub count: u16,
+}
+
+impl Default for LootDrop {
+    fn default() -> Self {
+        Self { chance: 1.0, value: 10, count: 1 }
+    }
+}
+
+/// Designer-facing enemy description.
+///
+/// Missing fields fall back to `Default` (the original stationary target).
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct EnemyArchetype {
+    pub health: i32,
+    /// Armour hits absorbed before `Health` takes damage.
+    pub armour: u16,
+    pub shape: EnemyShape,
+    pub sprite: SpriteDef,
+    pub weight: f32,
+    pub resistances: Resistances,
+    pub movement: Movement,
+    /// Optional low-health override of `movement`.
+    pub flee: Option<Flee>,
+    /// Sight and hearing; without it the enemy always knows where the player is.
+    pub perception: Option<Perception>,
+    pub emitter: Option<EmitterDef>,
+    /// Behaviour tree asset path; the tree then drives movement and firing.
+    pub behaviour: Option<String>,
+    /// Points for the kill, before the combo multiplier.
+    pub score: u32,
+    pub loot: Vec<LootDrop>,
+}
+
+impl Default for EnemyArchetype {
+    fn default() -> Self {
+        Self {
+            health: 5,
+            armour: 3,
+            shape: EnemyShape::Circle { radius: 16.0 },
+            sprite: SpriteDef::default(),
+            weight: 3.0,
+            resistances: Resistances::default(),
+            movement: Movement::Stationary,
+            flee: None,
+            perception: None,
+            emitter: None,
+            behaviour: None,
+            score: crate::plugins::score::KILL_SCORE,
+            loot: Vec::new(),
+        }
+    }
+}
+
+impl RonAsset for EnemyArchetype {
+    const EXTENSIONS: &'static [&'static str] = &["enemy.ron"];
+}
+
+/// A loaded archetype, ready to spawn.
+#[derive(Clone, Debug)]
+pub struct Archetype {
+    pub def: EnemyArchetype,
+    pub pattern: Option<Handle<BulletPattern>>,
+    pub behaviour: Option<Handle<BehaviourTree>>,
+}
+
+/// Archetypes by id.
+#[derive(Resource, Debug, Default)]
+pub struct EnemyArchetypes {
+    loading: Vec<(String, Handle<EnemyArchetype>)>,
+    ready: HashMap<String, Archetype>,
+}
+
+impl EnemyArchetypes {
+    /// Add a loaded archetype (with the emitter's pattern and the behaviour tree handles,
+    /// if any).
+    pub fn insert(
+        &mut self,
+        id: impl Into<String>,
+        def: EnemyArchetype,
+        pattern: Option<Handle<BulletPattern>>,
+        behaviour: Option<Handle<BehaviourTree>>,
+    ) {
+        self.ready.insert(id.into(), Archetype { def, pattern, behaviour });
+    }
+
+    #[inline]
+    pub fn get(&self, id: &str) -> Option<&Archetype> {
+        self.ready.get(id)
+    }
+
+    /// No file is still loading.
+    #[inline]
+    pub fn is_ready(&self) -> bool {
+        self.loading.is_empty()
+    }
+}
+
+/// Score value of an enemy's kill (copied from its archetype).
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreValue(pub u32);
+
+/// Loot rolled when the enemy dies (copied from its archetype).
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct LootTable(pub Vec<LootDrop>);
+
+/// Seeded stream for loot rolls.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LootRng(pub SeededRng);
+
+/// Pickups of one drop are spread on a small ring around the corpse.
+const LOOT_SCATTER: f32 = 14.0;
+
+impl LootTable {
+    /// Roll every drop; calls `spawn(pos, value)` for each pickup.
+    pub fn roll(&self, rng: &mut SeededRng, at: Vec2, mut spawn: impl FnMut(Vec2, u32)) {
+        for drop in &self.0 {
+            if !rng.chance(drop.chance) { continue; }
+            for i in 0..drop.count {
+                let offset = if drop.count > 1 {
+                    Vec2::from_angle(std::f32::consts::TAU * i as f32 / drop.count as f32) * LOOT_SCATTER
+                } else {
+                    Vec2::ZERO
+                };
+                spawn(at + offset, drop.value);
+            }
+        }
+    }
+}
+
+/// Spawn one enemy of archetype `id` at `pos`.
+///
+/// Visuals are asset-free: plain sprites and simple colliders. Returns `None` (with a
+/// warning) for an id that isn't loaded, so a typo in a wave file skips one spawn
+/// instead of ending the run.
+pub fn spawn_enemy(commands: &mut Commands, archetypes: &EnemyArchetypes, id: &str, pos: Vec2) -> Option<Entity> {
+    let Some(archetype) = archetypes.get(id) else {
+        warn!("unknown enemy archetype {id:?}");
+        return None;
+    };
+    let def = &archetype.def;
+    let (r, g, b) = def.sprite.color;
+    let body = Color::srgb(r, g, b);
+    let size = def.sprite.size.map_or(def.shape.size(), |(w, h)| Vec2::new(w, h));
+
+    // Enemy collision intent:
+    // - enemy collides with world, player, and player bullets.
+    let enemy_layers = CollisionLayers::new(
+        Layer::Enemy,
+        [Layer::World, Layer::Player, Layer::PlayerBullet],
+    );
+
+    let mut enemy = commands.spawn((
+        (
+            Name::new(format!("Enemy({id})")),
+            Enemy,
+            Armour { hits_remaining: def.armour, max_hits: def.armour },
+            Health { hp: def.health },
+            EnemyLifeState::Alive,
+            Knockback::default(),
+            Weight(def.weight),
+            def.resistances,
+            StatusEffects::default(),
+            ScoreValue(def.score),
+            LootTable(def.loot.clone()),
+        ),
+        (def.movement, MovementClock::default(), NavPath::default(), Separation::default()),
+        ArmourFx::new().with_body_color(body),
+        Sprite { color: body, custom_size: Some(size), ..default() },
+        Transform::from_translation(pos.extend(1.0)),
+        // Dynamic so walls block them; velocity is owned by `apply_enemy_movement`.
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        def.shape.collider(),
+        enemy_layers,
+        Occluder2d::circle(def.shape.bounding_radius()),
+        DespawnOnExit(GameState::InGame),
+    ));
+
+    if let Some(flee) = def.flee {
+        enemy.insert(flee);
+    }
+    if let Some(perception) = def.perception {
+        // Start out looking toward the middle of the arena.
+        enemy.insert((perception, Awareness::facing(-pos)));
+    }
+    if let (Some(emitter), Some(pattern)) = (&def.emitter, &archetype.pattern) {
+        enemy.insert(Emitter::new(pattern.clone()).with_delay(emitter.warmup_secs));
+    }
+    if let Some(tree) = &archetype.behaviour {
+        enemy.insert(Brain::new(tree.clone()));
+    }
+    Some(enemy.id())
+}
+
+pub fn load_enemy_archetypes(asset_server: Res<AssetServer>, mut archetypes: ResMut<EnemyArchetypes>) {
+    archetypes.loading = ARCHETYPE_IDS
+        .iter()
+        .map(|id| (id.to_string(), asset_server.load(format!("enemies/{id}.enemy.ron"))))
+        .collect();
+}
+
+/// Move loaded definitions into the registry (and start loading their patterns and
+/// behaviour trees).
+pub fn resolve_enemy_archetypes(
+    asset_server: Res<AssetServer>,
+    defs: Res<Assets<EnemyArchetype>>,
+    mut archetypes: ResMut<EnemyArchetypes>,
+) {
+    if archetypes.is_ready() { return; }
+
+    let loading = std::mem::take(&mut archetypes.loading);
+    for (id, handle) in loading {
+        if let Some(def) = defs.get(&handle) {
+            let pattern = def.emitter.as_ref().map(|e| asset_server.load(e.pattern.clone()));
+            let behaviour = def.behaviour.as_ref().map(|path| asset_server.load(path.clone()));
+            archetypes.insert(id, def.clone(), pattern, behaviour);
+        } else if asset_server.load_state(handle.id()).is_failed() {
+            warn!("enemy archetype {id:?} failed to load");
+        } else {
+            archetypes.loading.push((id, handle));
+        }
+    }
+}
+
+pub fn reset_loot_rng(mut rng: ResMut<LootRng>) {
+    rng.0 = SeededRng::new(LOOT_SEED);
+}
+
+/// Spawn the pickups a loot table rolled.
+pub fn drop_loot(commands: &mut Commands, rng: &mut LootRng, table: &LootTable, at: Vec2) {
+    table.roll(&mut rng.0, at, |pos, value| {
+        commands.spawn(score_pickup_bundle(pos, value));
+    });
+}
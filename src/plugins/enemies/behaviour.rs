@@ -0,0 +1,422 @@
+//! Behaviour trees: enemy decisions written as data and ticked on the fixed step.
+//!
+//! # Data flow
+//! ```text
+//!   assets/behaviours/<name>.bt.ron ──(RonAssetLoader)──> Assets<BehaviourTree> (flattened)
+//!   archetype `behaviour` path ──> spawn_enemy inserts Brain (tree handle + node memory)
+//!
+//!   FixedPostUpdate (after update_perception, before update_nav_paths)
+//!     tick_brains
+//!       Senses (position, player, Health, Armour, emitter?) + BrainRng ──> tree tick
+//!       ──> Brain::walk (read by the movement systems instead of Movement)
+//!       ──> Emitter::enabled (only while an Attack runs)
+//! ```
+//!
+//! # Nodes
+//! - `Sequence([..])`: children in order; fails at the first failure.
+//! - `Selector([..])`: children in order; succeeds at the first success.
+//! - `RandomChoice([(weight, node), ..])`: one child, picked by weight when it starts.
+//! - `MoveTo(to, speed, within, give_up_secs)`: walk (routed like a chaser) until within
+//!   `within` px of `Player`, a `Point(x, y)` or a random point `Wander(radius)` around
+//!   where it started; fails after `give_up_secs` (0 = never).
+//! - `Attack(secs)`: fire the enemy's emitter for `secs`; fails without an emitter.
+//! - `Wait(secs)`: stand still.
+//! - `CheckHealth(at_most)`, `CheckArmourUp`: succeed or fail at once.
+//!
+//! A node that is still running resumes on the next tick; nothing interrupts it. When the
+//! root finishes, it starts over on the next tick.
+//!
+//! # Determinism
+//! Every random pick (choices, wander points) comes from one `BrainRng` stream, reset on
+//! entering `InGame` like `LootRng`. Brains draw from it in query order, which only
+//! depends on the spawn history, so the same seed and the same run make the same
+//! decisions. `BehaviourTree::tick` is pure, so tests can step a brain directly.
+//!
+//! An enemy with a brain ignores its `Movement` and perception's say over its emitter:
+//! the tree owns both.
+
+use std::f32::consts::TAU;
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::common::ron_asset::RonAsset;
+use crate::common::rng::SeededRng;
+use crate::plugins::projectiles::components::{Armour, Enemy, Health, PlayerEntity};
+use crate::plugins::projectiles::emitter::Emitter;
+
+use super::movement::Movement;
+use super::EnemyLifeState;
+
+/// Seed for behaviour tree rolls on entering `InGame`.
+pub const BRAIN_SEED: u64 = 0x5EED_B7EE;
+
+/// Where a `MoveTo` walks.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum MoveTarget {
+    /// The player's current position (followed while the node runs).
+    Player,
+    /// A fixed point in the arena.
+    Point(f32, f32),
+    /// A random point within this radius of where the node started.
+    Wander(f32),
+}
+
+/// A behaviour tree node as written in a `.bt.ron` file.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum Node {
+    Sequence(Vec<Node>),
+    Selector(Vec<Node>),
+    RandomChoice(Vec<(f32, Node)>),
+    MoveTo {
+        to: MoveTarget,
+        speed: f32,
+        within: f32,
+        #[serde(default)]
+        give_up_secs: f32,
+    },
+    Attack { secs: f32 },
+    Wait { secs: f32 },
+    CheckHealth { at_most: i32 },
+    CheckArmourUp,
+}
+
+/// One node of a flattened tree, without its children.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Sequence,
+    Selector,
+    /// Child weights, in child order.
+    RandomChoice(Vec<f32>),
+    MoveTo { to: MoveTarget, speed: f32, within: f32, give_up_secs: f32 },
+    Attack { secs: f32 },
+    Wait { secs: f32 },
+    CheckHealth { at_most: i32 },
+    CheckArmourUp,
+}
+
+/// Nodes in pre-order; `end` is the index just past the node's subtree.
+#[derive(Clone, Debug, PartialEq)]
+struct Slot {
+    op: Op,
+    end: usize,
+}
+
+/// A loaded behaviour tree (the file's root node, flattened on load).
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(from = "Node")]
+pub struct BehaviourTree {
+    nodes: Vec<Slot>,
+}
+
+impl RonAsset for BehaviourTree {
+    const EXTENSIONS: &'static [&'static str] = &["bt.ron"];
+}
+
+impl From<Node> for BehaviourTree {
+    fn from(root: Node) -> Self {
+        fn flatten(node: Node, out: &mut Vec<Slot>) {
+            let (op, children) = match node {
+                Node::Sequence(children) => (Op::Sequence, children),
+                Node::Selector(children) => (Op::Selector, children),
+                Node::RandomChoice(choices) => {
+                    let (weights, children) = choices.into_iter().unzip();
+                    (Op::RandomChoice(weights), children)
+                }
+                Node::MoveTo { to, speed, within, give_up_secs } => {
+                    (Op::MoveTo { to, speed, within, give_up_secs }, Vec::new())
+                }
+                Node::Attack { secs } => (Op::Attack { secs }, Vec::new()),
+                Node::Wait { secs } => (Op::Wait { secs }, Vec::new()),
+                Node::CheckHealth { at_most } => (Op::CheckHealth { at_most }, Vec::new()),
+                Node::CheckArmourUp => (Op::CheckArmourUp, Vec::new()),
+            };
+            let at = out.len();
+            out.push(Slot { op, end: 0 });
+            for child in children {
+                flatten(child, out);
+            }
+            out[at].end = out.len();
+        }
+
+        let mut nodes = Vec::new();
+        flatten(root, &mut nodes);
+        Self { nodes }
+    }
+}
+
+/// Result of ticking a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    Running,
+}
+
+/// What a brain knows this tick.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Senses {
+    pub pos: Vec2,
+    pub player: Vec2,
+    pub hp: i32,
+    pub armour_up: bool,
+    pub has_emitter: bool,
+}
+
+/// Per-node memory while the node runs (reset when it finishes).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Memory {
+    running: bool,
+    /// Composites: index of the current child.
+    child: usize,
+    /// Leaves: seconds since the node started.
+    clock: f32,
+    /// `MoveTo`: the point picked when it started.
+    goal: Vec2,
+}
+
+/// An enemy's behaviour tree and where it is in it.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Brain {
+    pub tree: Handle<BehaviourTree>,
+    memory: Vec<Memory>,
+    /// Walk the tree asked for this tick (`None`: stand still).
+    pub walk: Option<(Movement, Vec2)>,
+    /// An `Attack` ran this tick.
+    pub firing: bool,
+    /// Leaf that is still running after this tick, if any.
+    pub active: Option<usize>,
+}
+
+impl Brain {
+    pub fn new(tree: Handle<BehaviourTree>) -> Self {
+        Self { tree, ..default() }
+    }
+}
+
+/// Seeded stream for behaviour tree rolls.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BrainRng(pub SeededRng);
+
+/// Mutable state for one tick.
+struct Tick<'a> {
+    brain: &'a mut Brain,
+    senses: &'a Senses,
+    rng: &'a mut SeededRng,
+    dt: f32,
+}
+
+impl BehaviourTree {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Node `i` (pre-order, root first).
+    #[inline]
+    pub fn op(&self, i: usize) -> &Op {
+        &self.nodes[i].op
+    }
+
+    /// Children of node `i`, in order.
+    fn children(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let end = self.nodes[i].end;
+        std::iter::successors(Some(i + 1), move |&c| Some(self.nodes[c].end)).take_while(move |&c| c < end)
+    }
+
+    /// Advance `brain` by `dt`: resume the running node (or start over from the root) and
+    /// record the walk, firing and active leaf it ends on.
+    pub fn tick(&self, brain: &mut Brain, senses: &Senses, rng: &mut SeededRng, dt: f32) -> Status {
+        if brain.memory.len() != self.nodes.len() {
+            // First tick, or the file was reloaded with a different shape.
+            brain.memory.clear();
+            brain.memory.resize(self.nodes.len(), Memory::default());
+        }
+        brain.walk = None;
+        brain.firing = false;
+        brain.active = None;
+        if self.nodes.is_empty() { return Status::Failure; }
+
+        self.run(0, &mut Tick { brain, senses, rng, dt })
+    }
+
+    fn run(&self, i: usize, t: &mut Tick) -> Status {
+        let starting = !t.brain.memory[i].running;
+        if starting {
+            t.brain.memory[i] = Memory { running: true, child: i + 1, ..default() };
+        }
+
+        let status = match &self.nodes[i].op {
+            Op::Sequence => self.run_children(i, t, Status::Failure),
+            Op::Selector => self.run_children(i, t, Status::Success),
+            Op::RandomChoice(weights) => {
+                if starting {
+                    let pick = pick_weighted(weights, t.rng);
+                    t.brain.memory[i].child = pick
+                        .and_then(|k| self.children(i).nth(k))
+                        .unwrap_or(self.nodes[i].end);
+                }
+                let child = t.brain.memory[i].child;
+                if child == self.nodes[i].end { Status::Failure } else { self.run(child, t) }
+            }
+            &Op::MoveTo { to, speed, within, give_up_secs } => {
+                if starting {
+                    t.brain.memory[i].goal = match to {
+                        MoveTarget::Player => t.senses.player,
+                        MoveTarget::Point(x, y) => Vec2::new(x, y),
+                        MoveTarget::Wander(radius) => {
+                            let angle = t.rng.next_f32() * TAU;
+                            t.senses.pos + Vec2::from_angle(angle) * radius * t.rng.next_f32().sqrt()
+                        }
+                    };
+                }
+                let goal = if to == MoveTarget::Player { t.senses.player } else { t.brain.memory[i].goal };
+                let memory = &mut t.brain.memory[i];
+                memory.clock += t.dt;
+
+                if t.senses.pos.distance(goal) <= within {
+                    Status::Success
+                } else if give_up_secs > 0.0 && memory.clock >= give_up_secs {
+                    Status::Failure
+                } else {
+                    t.brain.walk = Some((Movement::Chase { speed, stop_distance: 0.0 }, goal));
+                    Status::Running
+                }
+            }
+            &Op::Attack { secs } => {
+                if !t.senses.has_emitter { return self.finish(i, t, Status::Failure); }
+                let memory = &mut t.brain.memory[i];
+                memory.clock += t.dt;
+                if memory.clock >= secs {
+                    Status::Success
+                } else {
+                    t.brain.firing = true;
+                    Status::Running
+                }
+            }
+            &Op::Wait { secs } => {
+                let memory = &mut t.brain.memory[i];
+                memory.clock += t.dt;
+                if memory.clock >= secs { Status::Success } else { Status::Running }
+            }
+            &Op::CheckHealth { at_most } => status_of(t.senses.hp <= at_most),
+            Op::CheckArmourUp => status_of(t.senses.armour_up),
+        };
+
+        self.finish(i, t, status)
+    }
+
+    /// Run a composite's children from the current one until one returns `stop_on` (which
+    /// the composite returns) or all are done (the opposite).
+    fn run_children(&self, i: usize, t: &mut Tick, stop_on: Status) -> Status {
+        loop {
+            let child = t.brain.memory[i].child;
+            if child == self.nodes[i].end {
+                return if stop_on == Status::Failure { Status::Success } else { Status::Failure };
+            }
+            match self.run(child, t) {
+                Status::Running => return Status::Running,
+                status if status == stop_on => return status,
+                _ => t.brain.memory[i].child = self.nodes[child].end,
+            }
+        }
+    }
+
+    /// Record a running leaf, or clear a finished node's memory.
+    fn finish(&self, i: usize, t: &mut Tick, status: Status) -> Status {
+        if status != Status::Running {
+            t.brain.memory[i] = Memory::default();
+        } else if self.nodes[i].end == i + 1 {
+            t.brain.active = Some(i);
+        }
+        status
+    }
+}
+
+#[inline]
+fn status_of(ok: bool) -> Status {
+    if ok { Status::Success } else { Status::Failure }
+}
+
+/// Index picked in proportion to `weights` (negative weights count as 0), or `None`
+/// when nothing has weight.
+fn pick_weighted(weights: &[f32], rng: &mut SeededRng) -> Option<usize> {
+    let total: f32 = weights.iter().map(|w| w.max(0.0)).sum();
+    if total <= 0.0 { return None; }
+
+    let mut roll = rng.next_f32() * total;
+    for (k, w) in weights.iter().enumerate() {
+        let w = w.max(0.0);
+        if roll < w { return Some(k); }
+        roll -= w;
+    }
+    // Rounding left the roll just past the end: the last weighted child.
+    weights.iter().rposition(|w| *w > 0.0)
+}
+
+pub fn reset_brain_rng(mut rng: ResMut<BrainRng>) {
+    rng.0 = SeededRng::new(BRAIN_SEED);
+}
+
+/// Enemies with a brain, what it senses about them, and the emitter it drives.
+type Thinkers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Position,
+        &'static Health,
+        &'static Armour,
+        &'static EnemyLifeState,
+        &'static mut Brain,
+        Option<&'static mut Emitter>,
+    ),
+    With<Enemy>,
+>;
+
+/// Tick every alive enemy's brain and let it drive the emitter.
+///
+/// Brains whose tree hasn't loaded stand still.
+pub fn tick_brains(
+    time: Res<Time<Fixed>>,
+    trees: Res<Assets<BehaviourTree>>,
+    mut rng: ResMut<BrainRng>,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Position, Without<Enemy>>,
+    mut q: Thinkers,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity missing Position").0;
+    let dt = time.delta_secs();
+
+    for (pos, hp, armour, life, mut brain, emitter) in &mut q {
+        if !matches!(life, EnemyLifeState::Alive) {
+            brain.walk = None;
+            continue;
+        }
+        let Some(tree) = trees.get(&brain.tree) else {
+            brain.walk = None;
+            continue;
+        };
+
+        let senses = Senses {
+            pos: pos.0,
+            player: target,
+            hp: hp.hp,
+            armour_up: armour.is_up(),
+            has_emitter: emitter.is_some(),
+        };
+        tree.tick(&mut brain, &senses, &mut rng.0, dt);
+
+        if let Some(mut emitter) = emitter
+            && emitter.enabled != brain.firing
+        {
+            emitter.enabled = brain.firing;
+        }
+    }
+}
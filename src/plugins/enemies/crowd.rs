@@ -0,0 +1,161 @@
+//! Crowd movement: one shared flow field toward the player, plus separation so enemies
+//! don't stack.
+//!
+//! # Data flow
+//! ```text
+//!   FixedPostUpdate (before apply_enemy_movement)
+//!     update_crowd_flow   every FLOW_REFRESH_SECS, if the player's cell or the grid changed
+//!         NavGrid::flow_field(player) ──> CrowdFlow
+//!     compute_separation  alive enemies bucketed by SEPARATION_RADIUS cells
+//!         neighbours within the radius ──> Separation (push away, length ≤ 1)
+//!     apply_enemy_movement
+//!         Swarm: CrowdFlow direction × speed
+//!         every mover: + Separation × SEPARATION_WEIGHT × speed (capped at speed)
+//! ```
+//!
+//! # Cost
+//! - The flow field is one Dijkstra pass over the arena grid (a few hundred cells), at
+//!   most `1 / FLOW_REFRESH_SECS` times a second, whatever the crowd size.
+//! - Separation sorts agents by bucket once per tick, then each agent scans the 3×3
+//!   buckets around it: O(n log n) rather than O(n²) pairs. The sorted buffer is kept
+//!   between ticks, so a steady crowd doesn't allocate.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::plugins::projectiles::components::{Enemy, PlayerEntity};
+use crate::plugins::world::nav::{FlowField, NavGrid};
+
+use super::movement::Movement;
+use super::EnemyLifeState;
+
+/// Seconds between flow field refreshes.
+pub const FLOW_REFRESH_SECS: f32 = 0.1;
+
+/// Enemies closer than this (px, centre to centre) push each other apart.
+pub const SEPARATION_RADIUS: f32 = 36.0;
+
+/// Separation strength, as a fraction of the mover's own speed.
+pub const SEPARATION_WEIGHT: f32 = 1.2;
+
+/// Shared flow field toward the player.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CrowdFlow {
+    pub field: FlowField,
+    since_refresh: f32,
+    /// Player cell and grid version the field was built for.
+    computed_for: Option<(IVec2, u64)>,
+}
+
+/// Push away from nearby enemies, recomputed every tick (always present on enemies).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Separation(pub Vec2);
+
+pub fn update_crowd_flow(
+    time: Res<Time<Fixed>>,
+    grid: Res<NavGrid>,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Position, Without<Enemy>>,
+    mut flow: ResMut<CrowdFlow>,
+) {
+    flow.since_refresh += time.delta_secs();
+    if flow.computed_for.is_some() && flow.since_refresh < FLOW_REFRESH_SECS { return; }
+    flow.since_refresh = 0.0;
+
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity missing Position").0;
+    let key = grid.cell_at(target).map(|cell| (cell, grid.version()));
+    if key.is_some() && key == flow.computed_for { return; }
+
+    flow.field = grid.flow_field(target);
+    flow.computed_for = key;
+}
+
+/// Separation bucket containing `p` (a signed grid of `SEPARATION_RADIUS` cells).
+#[inline]
+fn bucket_cell(p: Vec2) -> IVec2 {
+    (p / SEPARATION_RADIUS).floor().as_ivec2()
+}
+
+/// Sort key for a bucket (row-major).
+#[inline]
+fn bucket_key(c: IVec2) -> i64 {
+    ((c.y as i64) << 32) | (c.x as u32 as i64)
+}
+
+/// One alive enemy for the separation pass: (bucket, position, agent index).
+type Agent = (i64, Vec2, u32);
+
+/// Sum of pushes on `sorted[s]` from every agent within `SEPARATION_RADIUS`, clamped to 1.
+///
+/// `sorted` is sorted by bucket. Pushes fade linearly to 0 at the radius; two agents on
+/// the exact same spot are split in opposite directions picked from their indices, so
+/// they still separate, deterministically.
+fn separation_at(sorted: &[Agent], s: usize) -> Vec2 {
+    let (_, p, a) = sorted[s];
+    let c = bucket_cell(p);
+    let mut push = Vec2::ZERO;
+
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            let key = bucket_key(c + IVec2::new(dx, dy));
+            let start = sorted.partition_point(|(k, _, _)| *k < key);
+            for &(k, q, b) in &sorted[start..] {
+                if k != key { break; }
+                if b == a { continue; }
+
+                let away = p - q;
+                let dist = away.length();
+                if dist >= SEPARATION_RADIUS { continue; }
+                let dir = if dist > 1e-3 {
+                    away / dist
+                } else {
+                    let split = Vec2::from_angle(a.min(b) as f32 * 2.4);
+                    if a < b { split } else { -split }
+                };
+                push += dir * (1.0 - dist / SEPARATION_RADIUS);
+            }
+        }
+    }
+    push.clamp_length_max(1.0)
+}
+
+/// Recompute every enemy's `Separation` from alive neighbours.
+///
+/// Two passes over the same query: agents are numbered in iteration order, which is
+/// stable within one run of the system.
+pub fn compute_separation(
+    mut sorted: Local<Vec<Agent>>,
+    mut pushes: Local<Vec<Vec2>>,
+    mut q: Query<(&Position, &EnemyLifeState, &mut Separation), With<Enemy>>,
+) {
+    sorted.clear();
+    sorted.extend(
+        q.iter()
+            .filter(|(_, life, _)| matches!(life, EnemyLifeState::Alive))
+            .enumerate()
+            .map(|(i, (pos, _, _))| (bucket_key(bucket_cell(pos.0)), pos.0, i as u32)),
+    );
+    sorted.sort_unstable_by_key(|(k, _, _)| *k);
+
+    pushes.clear();
+    pushes.resize(sorted.len(), Vec2::ZERO);
+    for (s, &(_, _, agent)) in sorted.iter().enumerate() {
+        pushes[agent as usize] = separation_at(&sorted, s);
+    }
+
+    let mut alive = pushes.iter();
+    for (_, life, mut sep) in &mut q {
+        sep.0 = if matches!(life, EnemyLifeState::Alive) {
+            *alive.next().expect("alive count changed between passes")
+        } else {
+            Vec2::ZERO
+        };
+    }
+}
+
+/// Desired velocity contribution from `sep` for `movement`.
+#[inline]
+pub fn separation_velocity(movement: Movement, sep: Separation) -> Vec2 {
+    sep.0 * movement.speed() * SEPARATION_WEIGHT
+}
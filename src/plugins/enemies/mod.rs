@@ -1,5 +1,8 @@
-//! Enemies plugin: static targets with Health + Armour + a short death state,
-//! plus "game feel" global effects (screen flash, camera shake, hitstop/slowmo).
+//! Enemies plugin: enemies built from archetype files (see `archetype`) with Health +
+//! Armour + a short death state, movement behaviours (see `movement`), sight and hearing
+//! (see `perception`) and optional behaviour trees (see `behaviour`), spawned in waves
+//! from a script file (see `waves`), plus "game feel" global effects
+//! (screen flash, camera shake, hitstop/slowmo).
 //!
 //! ---------------------------
 //! HOW THIS IS DESIGNED (ECS)
@@ -12,7 +15,7 @@
 //!    - `FxHandles` caches entity IDs so hot paths avoid repeated world scanning.
 //!
 //! 2) RULES mutate facts in predictable places:
-//!    - collision system (elsewhere) updates Armour/Health.
+//!    - the combat plugin's `apply_damage` updates Armour/Health and emits `DamageEvent`.
 //!    - this module reads those facts and transitions EnemyLifeState.
 //!
 //! 3) PRESENTATION is derived from facts:
@@ -45,15 +48,30 @@
 //! The timers tick using real (wall-clock) time so they still progress even while
 //! virtual time is frozen.
 
+pub mod archetype;
+pub mod behaviour;
+pub mod crowd;
+pub mod movement;
+pub mod perception;
+pub mod waves;
+
 use avian2d::prelude::*;
 use bevy::prelude::*;
-use bevy::state::state_scoped::DespawnOnExit;
 use bevy::time::{Fixed, Real, Virtual};
-use bevy_firefly::prelude::Occluder2d;
 
+use crate::common::ron_asset::register_ron_asset;
 use crate::common::state::GameState;
 use crate::plugins::projectiles::components::{Armour, Enemy, Health};
+use crate::plugins::combat::{apply_damage, DamageEvent};
+use crate::plugins::projectiles::cancel::apply_bullet_cancels;
+use crate::plugins::projectiles::emitter::Emitter;
+use crate::plugins::projectiles::explosion::apply_explosions;
+use crate::plugins::projectiles::messages::{BulletCancel, Explosion, Gunshot};
 use crate::plugins::projectiles::layers::Layer;
+use crate::plugins::score::{RunStats, KILL_SCORE};
+use crate::plugins::world::nav::sync_nav_grid;
+
+use archetype::{drop_loot, LootRng, LootTable, ScoreValue};
 
 // We prefer using a specific camera marker for determinism.
 // If your project always spawns exactly one main camera, caching it is ideal.
@@ -160,24 +178,30 @@ pub struct PendingDespawn;
 /// That keeps performance stable and makes behaviour easy to reason about.
 #[derive(Component, Debug, Clone)]
 pub struct ArmourFx {
-    last_hits_remaining: u16,
     hit_flash: UnitF32,
     break_pulse: UnitF32,
     crackle_remaining: RealSeconds,
     crackle_phase: f32,
+    /// Base colour once armour is gone (from the archetype).
+    body_color: Color,
 }
 
 impl ArmourFx {
-    fn new(initial_hits: u16) -> Self {
+    fn new() -> Self {
         Self {
-            last_hits_remaining: initial_hits,
             hit_flash: UnitF32::default(),
             break_pulse: UnitF32::default(),
             crackle_remaining: RealSeconds::default(),
             crackle_phase: 0.0,
+            body_color: Color::srgb(0.9, 0.25, 0.25),
         }
     }
 
+    fn with_body_color(mut self, color: Color) -> Self {
+        self.body_color = color;
+        self
+    }
+
     /// Used to skip extra colour math when no effect is active.
     #[inline]
     fn any_active(&self) -> bool {
@@ -272,6 +296,17 @@ impl GlobalFx {
         self.slowmo_remaining.set_max(self.slowmo_duration.get());
         self.slowmo_min_speed = 0.22;
     }
+
+    /// Explosion "feel preset": shake + flash scaled by `strength` in [0..1].
+    ///
+    /// Only a tiny hitstop and no slowmo: explosions can chain, and stacking time
+    /// effects on every blast would make rapid fire feel sluggish.
+    fn trigger_explosion(&mut self, strength: f32) {
+        let s = strength.clamp(0.0, 1.0);
+        self.trauma.add_clamped(0.25 + 0.45 * s);
+        self.flash = UnitF32::new_clamped(self.flash.get().max(0.35 * s));
+        self.hitstop.set_max(0.03 * s);
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -289,16 +324,40 @@ pub fn plugin(app: &mut App) {
     app.insert_resource(GlobalFx::default());
     app.insert_resource(FxHandles::default());
 
-    // Spawn enemies once per entry into InGame.
-    app.add_systems(OnEnter(GameState::InGame), spawn_targets);
+    // Archetypes: enemy definitions (RON assets), copied into a registry once loaded.
+    register_ron_asset::<archetype::EnemyArchetype>(app);
+    app.init_resource::<archetype::EnemyArchetypes>()
+        .init_resource::<archetype::LootRng>()
+        .add_systems(Startup, archetype::load_enemy_archetypes)
+        .add_systems(Update, archetype::resolve_enemy_archetypes)
+        .add_systems(OnEnter(GameState::InGame), archetype::reset_loot_rng);
+
+    // Behaviour trees (RON assets) for archetypes that name one; ticked with motion below.
+    register_ron_asset::<behaviour::BehaviourTree>(app);
+    app.init_resource::<behaviour::BrainRng>()
+        .add_systems(OnEnter(GameState::InGame), behaviour::reset_brain_rng);
+
+    // Waves: the director spawns enemies over time from the arena's wave script.
+    register_ron_asset::<waves::WaveScript>(app);
+    app.init_resource::<waves::WaveDirector>()
+        .init_resource::<Messages<waves::WaveStarted>>()
+        .init_resource::<Messages<waves::WaveCleared>>()
+        .add_systems(PostUpdate, update_wave_messages)
+        .add_systems(OnEnter(GameState::InGame), waves::start_waves)
+        .add_systems(
+            FixedUpdate,
+            waves::run_wave_director.run_if(in_state(GameState::InGame)),
+        );
 
     // Fixed-step lifecycle:
-    // - death trigger runs after collision resolution so it sees updated Health.
+    // - death trigger runs after collision resolution so it sees updated Health, and
+    //   before bullet cancels so a dying emitter's bullets are cleared the same tick.
     // - death progress animates and marks PendingDespawn when complete.
     app.add_systems(
         FixedPostUpdate,
         enemy_death_trigger
-            .after(crate::plugins::projectiles::collision::process_player_bullet_collisions)
+            .after(apply_damage)
+            .before(apply_bullet_cancels)
             .run_if(in_state(GameState::InGame)),
     );
 
@@ -309,18 +368,47 @@ pub fn plugin(app: &mut App) {
             .run_if(in_state(GameState::InGame)),
     );
 
+    // Fixed-step motion: behaviours + knockback set velocity before the physics step;
+    // the crowd flow field, separation, perception, behaviour trees and chasers' grid
+    // paths are refreshed first. Gunshots are written in `Update`, so their buffer
+    // advances here, after perception has listened (like hitscan requests).
+    app.init_resource::<crowd::CrowdFlow>();
+    app.add_systems(
+        FixedPostUpdate,
+        (
+            crowd::update_crowd_flow.after(sync_nav_grid),
+            crowd::compute_separation,
+            perception::update_perception,
+            update_gunshot_messages,
+            behaviour::tick_brains,
+            movement::update_nav_paths.after(sync_nav_grid),
+            movement::apply_enemy_movement,
+        )
+            .chain()
+            .before(PhysicsSystems::StepSimulation)
+            .run_if(in_state(GameState::InGame)),
+    );
+
     // Fixed-step armour visuals:
-    // - read Armour changes
+    // - subscribe to DamageEvent (armour hits / breaks)
     // - update local ArmourFx
     // - trigger global effects on break
     app.add_systems(
         FixedPostUpdate,
         armour_fx_update
-            .after(crate::plugins::projectiles::collision::process_player_bullet_collisions)
+            .after(apply_damage)
             .after(enemy_death_trigger)
             .run_if(in_state(GameState::InGame)),
     );
 
+    // Fixed-step explosion feedback: subscribe to Explosion messages.
+    app.add_systems(
+        FixedPostUpdate,
+        explosion_fx
+            .after(apply_explosions)
+            .run_if(in_state(GameState::InGame)),
+    );
+
     // PostUpdate boundary: ensure camera/overlay handles exist.
     // After this, apply_global_fx can run straight-line and fast.
     app.add_systems(
@@ -343,6 +431,23 @@ pub fn plugin(app: &mut App) {
     );
 }
 
+/// Maintain the gunshot message buffer on the fixed clock.
+///
+/// Shots are written in `Update` but heard in the fixed step; advancing after
+/// `update_perception` means no shot goes unheard in a frame that ran no fixed step.
+fn update_gunshot_messages(mut msgs: ResMut<Messages<Gunshot>>) {
+    msgs.update();
+}
+
+/// Maintain wave message buffers (written in the fixed step).
+fn update_wave_messages(
+    mut started: ResMut<Messages<waves::WaveStarted>>,
+    mut cleared: ResMut<Messages<waves::WaveCleared>>,
+) {
+    started.update();
+    cleared.update();
+}
+
 // -----------------------------------------------------------------------------
 // Spawn
 // -----------------------------------------------------------------------------
@@ -357,64 +462,44 @@ fn non_interacting_enemy_layers() -> CollisionLayers {
     CollisionLayers::new(Layer::Enemy, [] as [Layer; 0])
 }
 
-/// Spawn a few stationary targets.
-///
-/// This is intentionally asset-free: plain sprites and simple colliders.
-fn spawn_targets(mut commands: Commands) {
-    // Enemy collision intent:
-    // - enemy collides with world, player, and player bullets.
-    let enemy_layers = CollisionLayers::new(
-        Layer::Enemy,
-        [Layer::World, Layer::Player, Layer::PlayerBullet],
-    );
-
-    let initial_armour: u16 = 3;
-    let initial_hp: i32 = 5;
-
-    for (i, x) in [-400.0, -200.0, 0.0, 200.0, 400.0].into_iter().enumerate() {
-        commands.spawn((
-            Name::new(format!("EnemyTarget{i}")),
-            Enemy,
-            Armour {
-                hits_remaining: initial_armour,
-                max_hits: initial_armour,
-            },
-            Health { hp: initial_hp },
-            EnemyLifeState::Alive,
-            ArmourFx::new(initial_armour),
-            Sprite {
-                color: Color::srgb(0.9, 0.25, 0.25),
-                custom_size: Some(Vec2::splat(32.0)),
-                ..default()
-            },
-            Transform::from_xyz(x, 120.0, 1.0),
-            RigidBody::Static,
-            Collider::circle(16.0),
-            enemy_layers,
-            Occluder2d::circle(16.0),
-            DespawnOnExit(GameState::InGame),
-        ));
-    }
-}
+/// Enemy bullets within this radius of a dying emitter are cancelled into score pickups.
+const DEATH_CANCEL_RADIUS: f32 = 220.0;
 
 // -----------------------------------------------------------------------------
 // Rules: enemy death lifecycle
 // -----------------------------------------------------------------------------
 
+/// Enemies a kill can still land on, with everything their death changes or pays out.
+type Mortals<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Health,
+        &'static mut EnemyLifeState,
+        &'static mut CollisionLayers,
+        &'static mut Sprite,
+        &'static mut Transform,
+        Option<&'static mut Emitter>,
+        Option<&'static ScoreValue>,
+        Option<&'static LootTable>,
+    ),
+    (With<Enemy>, Without<PendingDespawn>),
+>;
+
 /// Transition Alive -> Dying when HP drops to 0.
 ///
 /// Note: this system does not despawn.
-/// It only transitions state and enforces "dying invariants" (stop collision interaction).
+/// It only transitions state and enforces "dying invariants" (stop collision interaction,
+/// stop emitting bullets). A dying emitter also cancels nearby enemy bullets. Each
+/// transition is one kill in `RunStats`.
 fn enemy_death_trigger(
-    mut q: Query<(
-        &Health,
-        &mut EnemyLifeState,
-        &mut CollisionLayers,
-        &mut Sprite,
-        &mut Transform,
-    ), (With<Enemy>, Without<PendingDespawn>)>,
+    mut commands: Commands,
+    mut cancels: MessageWriter<BulletCancel>,
+    mut stats: ResMut<RunStats>,
+    mut loot_rng: ResMut<LootRng>,
+    mut q: Mortals,
 ) {
-    for (hp, mut life, mut layers, mut sprite, mut tf) in &mut q {
+    for (hp, mut life, mut layers, mut sprite, mut tf, emitter, score, loot) in &mut q {
         if !matches!(*life, EnemyLifeState::Alive) {
             continue;
         }
@@ -424,6 +509,17 @@ fn enemy_death_trigger(
                 timer: Timer::from_seconds(0.35, TimerMode::Once),
             };
             *layers = non_interacting_enemy_layers();
+            stats.record_kill(score.map_or(KILL_SCORE, |s| s.0));
+            if let Some(loot) = loot {
+                drop_loot(&mut commands, &mut loot_rng, loot, tf.translation.truncate());
+            }
+            if let Some(mut emitter) = emitter {
+                emitter.enabled = false;
+                cancels.write(BulletCancel {
+                    pos: tf.translation.truncate(),
+                    radius: DEATH_CANCEL_RADIUS,
+                });
+            }
 
             // Immediate readability: a neutral tint and reset scale.
             sprite.color = Color::srgba(0.8, 0.8, 0.8, 1.0);
@@ -471,41 +567,40 @@ fn enemy_death_progress(
 
 /// Update local armour visuals and trigger global effects on armour break.
 ///
-/// This system reads gameplay truth (`Armour`) and writes presentation state (`ArmourFx`).
-/// It also triggers global feedback via `GlobalFx` when a break is detected.
+/// Hits and breaks come from `DamageEvent` (the damage pipeline's outcome), so nothing
+/// has to diff `Armour` between ticks. `Armour` itself is still read for the base colour.
+/// Global feedback is triggered via `GlobalFx` on a break.
 fn armour_fx_update(
     fixed_time: Res<Time<Fixed>>,
     mut global_fx: ResMut<GlobalFx>,
+    mut damage: MessageReader<DamageEvent>,
     mut q: Query<(&Armour, &mut ArmourFx, &mut Sprite, &EnemyLifeState), (With<Enemy>, Without<PendingDespawn>)>,
 ) {
     // Using Fixed time means hitstop/slowmo affects these visuals too.
     let dt = fixed_time.delta_secs();
 
-    for (armour, mut fx, mut sprite, life) in &mut q {
-        if !matches!(life, EnemyLifeState::Alive) {
-            continue;
-        }
+    for ev in damage.read() {
+        if !ev.was_armour_hit { continue; }
+        let Ok((_, mut fx, _, life)) = q.get_mut(ev.target) else { continue; };
+        if !matches!(life, EnemyLifeState::Alive) { continue; }
 
-        let new_hits = armour.hits_remaining;
-        let old_hits = fx.last_hits_remaining;
+        fx.hit_flash = UnitF32::new_clamped(1.0);
 
-        // Detect armour changes without events:
-        // - compare last seen value to current value
-        // - update last seen
-        if new_hits < old_hits {
-            fx.hit_flash = UnitF32::new_clamped(1.0);
+        if ev.armour_broken {
+            fx.break_pulse = UnitF32::new_clamped(1.0);
+            fx.crackle_remaining = RealSeconds::new(0.32);
+            fx.crackle_phase = 0.0;
 
-            // Break when it crosses to 0.
-            if new_hits == 0 && old_hits > 0 {
-                fx.break_pulse = UnitF32::new_clamped(1.0);
-                fx.crackle_remaining = RealSeconds::new(0.32);
-                fx.crackle_phase = 0.0;
+            global_fx.trigger_armour_break();
+        }
+    }
 
-                global_fx.trigger_armour_break();
-            }
+    for (armour, mut fx, mut sprite, life) in &mut q {
+        if !matches!(life, EnemyLifeState::Alive) {
+            continue;
         }
 
-        fx.last_hits_remaining = new_hits;
+        let new_hits = armour.hits_remaining;
 
         // Decay local FX toward zero.
         fx.hit_flash.decay_to_zero(8.0, dt);
@@ -519,7 +614,7 @@ fn armour_fx_update(
         let base = if new_hits > 0 {
             Color::srgb(0.35, 0.65, 1.0)
         } else {
-            Color::srgb(0.9, 0.25, 0.25)
+            fx.body_color
         };
 
         // Skip extra math when nothing is active.
@@ -563,6 +658,16 @@ fn armour_fx_update(
     }
 }
 
+/// Blast radius (pixels) that maps to full-strength explosion feedback.
+const EXPLOSION_FX_FULL_RADIUS: f32 = 160.0;
+
+/// Trigger the explosion preset for every detonation this step.
+fn explosion_fx(mut explosions: MessageReader<Explosion>, mut global_fx: ResMut<GlobalFx>) {
+    for ex in explosions.read() {
+        global_fx.trigger_explosion(ex.radius / EXPLOSION_FX_FULL_RADIUS);
+    }
+}
+
 // -----------------------------------------------------------------------------
 // PostUpdate boundary: establish invariants (cache camera + spawn overlay once)
 // -----------------------------------------------------------------------------
@@ -0,0 +1,284 @@
+//! Enemy movement behaviours: chase, strafe, orbit, and flee at low health.
+//!
+//! # Data flow
+//! ```text
+//!   FixedPostUpdate (before PhysicsSystems::StepSimulation, like the player's apply_movement)
+//!     apply_enemy_movement
+//!       Movement (+ Flee below its hp threshold) + player Position ──> steer() ──> desired
+//!       (perceiving enemies go after what they know instead: see `perception::pursuit`;
+//!       enemies with a behaviour tree walk where it says: see `behaviour`)
+//!       desired + crowd::Separation (see `crowd`) ──> capped at the behaviour's speed
+//!       desired × StatusEffects::speed_mult ──> Knockback::blend ──> LinearVelocity
+//! ```
+//!
+//! Enemies are dynamic bodies, so walls and the player block them, but this system owns
+//! their velocity every tick: contacts can't slowly push them around, and a hit's
+//! knockback takes control away for its stun window exactly as it does for the player.
+//!
+//! # Pathing
+//! Chasers head for the next waypoint of their `NavPath` instead of straight at the
+//! player, so they walk around obstacles. `update_nav_paths` asks the world's `NavGrid`
+//! for a new path only when the player changes cell or the grid itself changes; in
+//! between it just drops waypoints as they are reached. The stop distance is still
+//! measured to the player. Swarmers skip per-enemy paths and read the shared flow field
+//! instead (see `crowd`).
+//!
+//! `steer` is a pure function of two positions and the behaviour, so every behaviour is
+//! testable without a physics world. Per-enemy state is `MovementClock` (times strafe
+//! direction flips) and the cached `NavPath`.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::plugins::combat::status::StatusEffects;
+use crate::plugins::projectiles::components::{Enemy, Health, Knockback, PlayerEntity};
+use crate::plugins::world::nav::{FlowField, NavGrid, NAV_CELL};
+
+use super::crowd::{separation_velocity, CrowdFlow, Separation};
+use super::behaviour::Brain;
+use super::perception::{pursuit, Awareness};
+use super::EnemyLifeState;
+
+/// How an enemy moves on its own, relative to the player. Speeds are px/s.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum Movement {
+    /// Only knockback moves it.
+    #[default]
+    Stationary,
+    /// Head straight for the player, stopping `stop_distance` short.
+    Chase { speed: f32, stop_distance: f32 },
+    /// Keep between `min_distance` and `max_distance`, sidestepping while in the band;
+    /// the sidestep flips every `switch_secs` (0 = never).
+    Strafe { speed: f32, min_distance: f32, max_distance: f32, switch_secs: f32 },
+    /// Circle the player at `radius`.
+    Orbit { speed: f32, radius: f32, clockwise: bool },
+    /// Follow the shared crowd flow field to the player (hordes; no per-enemy path).
+    Swarm { speed: f32 },
+}
+
+impl Movement {
+    /// Top speed of the behaviour (0 when stationary).
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        match *self {
+            Movement::Stationary => 0.0,
+            Movement::Chase { speed, .. }
+            | Movement::Strafe { speed, .. }
+            | Movement::Orbit { speed, .. }
+            | Movement::Swarm { speed } => speed,
+        }
+    }
+}
+
+/// Run straight away from the player once `Health` drops to `below_hp` or less.
+///
+/// Overrides `Movement` while it applies.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct Flee {
+    pub below_hp: i32,
+    pub speed: f32,
+}
+
+/// Seconds of fixed time since the enemy spawned.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct MovementClock(pub f32);
+
+/// A waypoint counts as reached this close (px).
+const WAYPOINT_REACHED: f32 = NAV_CELL * 0.25;
+
+/// Cached grid path toward the player (always present; empty unless chasing).
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct NavPath {
+    /// Remaining waypoints, next first.
+    pub waypoints: Vec<Vec2>,
+    /// Player cell and grid version the path was computed for.
+    pub computed_for: Option<(IVec2, u64)>,
+}
+
+impl NavPath {
+    #[inline]
+    pub fn next(&self) -> Option<Vec2> {
+        self.waypoints.first().copied()
+    }
+}
+
+/// Desired velocity for `movement` at `pos`, with the player at `target`.
+pub fn steer(movement: Movement, pos: Vec2, target: Vec2, elapsed: f32) -> Vec2 {
+    let to_target = target - pos;
+    let dist = to_target.length();
+    let Some(dir) = to_target.try_normalize() else { return Vec2::ZERO; };
+
+    match movement {
+        Movement::Stationary => Vec2::ZERO,
+        Movement::Chase { speed, stop_distance } => {
+            if dist > stop_distance { dir * speed } else { Vec2::ZERO }
+        }
+        Movement::Swarm { speed } => dir * speed,
+        Movement::Strafe { speed, min_distance, max_distance, switch_secs } => {
+            if dist < min_distance {
+                -dir * speed
+            } else if dist > max_distance {
+                dir * speed
+            } else {
+                let flipped = switch_secs > 0.0 && (elapsed / switch_secs) as u32 % 2 == 1;
+                dir.perp() * if flipped { -speed } else { speed }
+            }
+        }
+        Movement::Orbit { speed, radius, clockwise } => {
+            // `-dir` is our offset from the player; its perp is the counter-clockwise tangent.
+            let tangent = if clockwise { dir.perp() } else { -dir.perp() };
+            // Pull back onto the circle: full strength one radius off, none on it.
+            let radial = ((dist - radius) / radius.max(1.0)).clamp(-1.0, 1.0);
+            (tangent + dir * radial).normalize_or_zero() * speed
+        }
+    }
+}
+
+/// `steer`, routed around obstacles: a moving chaser aims at its next waypoint (if it has
+/// a multi-step path) and a swarmer follows the flow field (where it has a direction).
+#[inline]
+fn route(movement: Movement, pos: Vec2, target: Vec2, path: &NavPath, flow: &FlowField, elapsed: f32) -> Vec2 {
+    let desired = steer(movement, pos, target, elapsed);
+    match movement {
+        Movement::Chase { speed, .. } if desired != Vec2::ZERO && path.waypoints.len() > 1 => {
+            path.next().map_or(desired, |next| (next - pos).normalize_or_zero() * speed)
+        }
+        Movement::Swarm { speed } => flow.direction_at(pos).map_or(desired, |dir| dir * speed),
+        _ => desired,
+    }
+}
+
+/// Flee velocity if `flee` applies at `hp`.
+#[inline]
+pub fn flee_velocity(flee: Flee, hp: i32, pos: Vec2, target: Vec2) -> Option<Vec2> {
+    (hp <= flee.below_hp).then(|| (pos - target).normalize_or_zero() * flee.speed)
+}
+
+/// Behaviour and target to move by: the brain's walk for enemies with a behaviour tree,
+/// otherwise `Movement` as far as the enemy knows where the player is.
+#[inline]
+fn plan(
+    movement: Movement,
+    awareness: Option<&Awareness>,
+    brain: Option<&Brain>,
+    player: Vec2,
+) -> Option<(Movement, Vec2)> {
+    match brain {
+        Some(brain) => brain.walk,
+        None => pursuit(movement, awareness, player),
+    }
+}
+
+/// Enemies whose plan may need a path.
+type Pathfinders<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Position,
+        &'static Movement,
+        &'static EnemyLifeState,
+        Option<&'static Awareness>,
+        Option<&'static Brain>,
+        &'static mut NavPath,
+    ),
+    With<Enemy>,
+>;
+
+/// Keep chasers' `NavPath`s current (including searchers walking to a last-known
+/// position).
+///
+/// Runs before `apply_enemy_movement`, after the grid has synced this tick.
+pub fn update_nav_paths(
+    grid: Res<NavGrid>,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Position, Without<Enemy>>,
+    mut q: Pathfinders,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity missing Position").0;
+
+    for (pos, movement, life, awareness, brain, mut path) in &mut q {
+        let goal = matches!(life, EnemyLifeState::Alive)
+            .then(|| plan(*movement, awareness, brain, target))
+            .flatten()
+            .filter(|(movement, _)| matches!(movement, Movement::Chase { .. }));
+        let Some((_, goal)) = goal else {
+            if path.computed_for.is_some() {
+                *path = NavPath::default();
+            }
+            continue;
+        };
+
+        let key = grid.cell_at(goal).map(|cell| (cell, grid.version()));
+        if path.computed_for != key {
+            path.waypoints = grid.find_path(pos.0, goal).unwrap_or_default();
+            path.computed_for = key;
+        }
+
+        // The goal waypoint is the player's (stale) position: steer at the player instead.
+        let reached = |p: &Vec2| p.distance_squared(pos.0) <= WAYPOINT_REACHED * WAYPOINT_REACHED;
+        while path.waypoints.len() > 1 && path.waypoints.first().is_some_and(reached) {
+            path.waypoints.remove(0);
+        }
+    }
+}
+
+/// Enemies that steer, with everything their velocity depends on.
+type Movers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Position,
+        &'static Movement,
+        &'static mut MovementClock,
+        Option<&'static Flee>,
+        Option<&'static Awareness>,
+        Option<&'static Brain>,
+        &'static Health,
+        &'static EnemyLifeState,
+        &'static StatusEffects,
+        &'static NavPath,
+        &'static Separation,
+        &'static mut Knockback,
+        &'static mut LinearVelocity,
+    ),
+    With<Enemy>,
+>;
+
+/// Drive enemy velocity from its behaviour, `Separation` and `Knockback`.
+///
+/// Dying enemies stop steering; their knockback still plays out.
+pub fn apply_enemy_movement(
+    time: Res<Time<Fixed>>,
+    flow: Res<CrowdFlow>,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Position, Without<Enemy>>,
+    mut q: Movers,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity missing Position").0;
+    let dt = time.delta_secs();
+
+    for (pos, movement, mut clock, flee, awareness, brain, hp, life, status, path, sep, mut knockback, mut vel) in &mut q {
+        clock.0 += dt;
+
+        let desired = if matches!(life, EnemyLifeState::Alive) {
+            let planned = plan(*movement, awareness, brain, target);
+            let desired = flee
+                .and_then(|f| flee_velocity(*f, hp.hp, pos.0, target))
+                .unwrap_or_else(|| {
+                    planned.map_or(Vec2::ZERO, |(movement, goal)| route(movement, pos.0, goal, path, &flow.field, clock.0))
+                });
+            // Separation only bends a mover's own motion: it never exceeds its top speed.
+            let mover = planned.map_or(*movement, |(movement, _)| movement);
+            let cap = desired.length().max(mover.speed());
+            (desired + separation_velocity(mover, *sep)).clamp_length_max(cap)
+        } else {
+            Vec2::ZERO
+        };
+
+        vel.0 = knockback.blend(desired * status.speed_mult());
+        knockback.tick(dt);
+    }
+}
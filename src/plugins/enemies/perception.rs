@@ -0,0 +1,251 @@
+//! Enemy perception: a view cone blocked by walls, hearing for the player's gunshots, and
+//! an alert state that decides what the enemy goes after.
+//!
+//! # Data flow
+//! ```text
+//!   Update
+//!     request_player_bullets ──> Gunshot (one per trigger pull / beam tick)
+//!   FixedPostUpdate (before update_nav_paths)
+//!     update_perception
+//!       player in the view cone ──> SpatialQuery::cast_ray against Layer::World ──> seen?
+//!       Gunshot within hearing_radius (through walls) ──> heard?
+//!       seen / heard / timers ──> Awareness (alert, facing, last_known)
+//!       Emitter::enabled = engaged
+//!     update_nav_paths / apply_enemy_movement
+//!       pursuit(): engaged → behaviour vs. the player; suspicious / searching → walk to
+//!       last_known; idle → stand still
+//! ```
+//!
+//! # Alert states
+//! - `Idle`: hasn't noticed anything; stands still, facing the way it spawned.
+//! - `Suspicious`: heard a shot; turns toward it and goes to look, for `suspicious_secs`.
+//! - `Engaged`: sees the player; runs its normal behaviour and fires.
+//! - `Searching`: lost sight of the player; goes to where it was last seen and looks
+//!   around for `search_secs`.
+//!
+//! Seeing the player engages from any state; a shot refreshes the timers. Enemies
+//! without `Perception` (hordes, turrets) always know where the player is.
+//!
+//! `perceive` takes the line-of-sight test as a closure, so the rules are testable
+//! without a physics world (like `hitscan::trace`).
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::ecs::message::MessageReader;
+use serde::Deserialize;
+
+use crate::plugins::projectiles::components::{Enemy, PlayerEntity};
+use crate::plugins::projectiles::emitter::Emitter;
+use crate::plugins::projectiles::layers::Layer;
+use crate::plugins::projectiles::messages::Gunshot;
+
+use super::movement::Movement;
+use super::EnemyLifeState;
+
+/// A searcher this close to the last-known position has arrived and starts looking around.
+pub const SEARCH_ARRIVED: f32 = 12.0;
+
+/// How fast a searcher sweeps its view cone once it has arrived.
+const SEARCH_TURN_RAD_PER_SEC: f32 = 2.0;
+
+/// How an enemy notices the player (from its archetype).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Perception {
+    /// Sight distance (px).
+    pub view_range: f32,
+    /// Full width of the view cone (degrees).
+    pub fov_deg: f32,
+    /// Player gunshots this close are heard, walls or not (px).
+    pub hearing_radius: f32,
+    /// Seconds a shot keeps the enemy suspicious.
+    pub suspicious_secs: f32,
+    /// Seconds spent searching after losing sight of the player.
+    pub search_secs: f32,
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Self { view_range: 400.0, fov_deg: 100.0, hearing_radius: 450.0, suspicious_secs: 4.0, search_secs: 5.0 }
+    }
+}
+
+impl Perception {
+    /// `target` is inside the view cone from `pos` looking along `facing` (walls ignored).
+    #[inline]
+    pub fn in_view_cone(&self, pos: Vec2, facing: Vec2, target: Vec2) -> bool {
+        let to_target = target - pos;
+        if to_target.length_squared() > self.view_range * self.view_range { return false; }
+        let Some(dir) = to_target.try_normalize() else { return true; };
+        facing.dot(dir) >= (self.fov_deg.to_radians() * 0.5).cos()
+    }
+
+    /// Closest of `shots` within hearing range of `pos`.
+    #[inline]
+    pub fn hear(&self, pos: Vec2, shots: &[Vec2]) -> Option<Vec2> {
+        shots
+            .iter()
+            .copied()
+            .filter(|s| s.distance_squared(pos) <= self.hearing_radius * self.hearing_radius)
+            .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+    }
+}
+
+/// What the enemy currently thinks of the player. Timers are seconds remaining.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Alert {
+    #[default]
+    Idle,
+    Suspicious { remaining: f32 },
+    Engaged,
+    Searching { remaining: f32 },
+}
+
+/// Per-enemy perception state (present with `Perception`).
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Awareness {
+    pub alert: Alert,
+    /// Unit direction the view cone points.
+    pub facing: Vec2,
+    /// Where the player was last seen, or the last shot was heard.
+    pub last_known: Vec2,
+}
+
+impl Awareness {
+    /// Idle, looking along `facing`.
+    pub fn facing(facing: Vec2) -> Self {
+        Self { alert: Alert::Idle, facing: facing.normalize_or(Vec2::NEG_Y), last_known: Vec2::ZERO }
+    }
+
+    /// Advance the alert state by `dt`, given where the player was `seen` and a shot
+    /// `heard` this tick.
+    pub fn step(&mut self, p: &Perception, seen: Option<Vec2>, heard: Option<Vec2>, dt: f32) {
+        if let Some(at) = seen {
+            self.alert = Alert::Engaged;
+            self.last_known = at;
+            return;
+        }
+        if let Some(at) = heard {
+            self.last_known = at;
+        }
+
+        self.alert = match self.alert {
+            Alert::Engaged => Alert::Searching { remaining: p.search_secs },
+            Alert::Searching { .. } if heard.is_some() => Alert::Searching { remaining: p.search_secs },
+            _ if heard.is_some() => Alert::Suspicious { remaining: p.suspicious_secs },
+            Alert::Suspicious { remaining } if remaining > dt => Alert::Suspicious { remaining: remaining - dt },
+            Alert::Searching { remaining } if remaining > dt => Alert::Searching { remaining: remaining - dt },
+            _ => Alert::Idle,
+        };
+    }
+
+    /// Point the view cone: at `last_known` while alert, sweeping once a search arrives.
+    pub fn turn(&mut self, pos: Vec2, dt: f32) {
+        match self.alert {
+            Alert::Idle => {}
+            Alert::Searching { .. } if pos.distance(self.last_known) <= SEARCH_ARRIVED => {
+                self.facing = Vec2::from_angle(SEARCH_TURN_RAD_PER_SEC * dt).rotate(self.facing);
+            }
+            _ => {
+                if let Some(dir) = (self.last_known - pos).try_normalize() {
+                    self.facing = dir;
+                }
+            }
+        }
+    }
+}
+
+/// One perception tick for an enemy at `pos`, with the player at `target`.
+///
+/// `los(from, to)` is true when no wall blocks the segment; it is only asked when the
+/// player is inside the view cone.
+pub fn perceive(
+    awareness: &mut Awareness,
+    p: &Perception,
+    pos: Vec2,
+    target: Vec2,
+    shots: &[Vec2],
+    dt: f32,
+    los: impl FnOnce(Vec2, Vec2) -> bool,
+) {
+    let seen = (p.in_view_cone(pos, awareness.facing, target) && los(pos, target)).then_some(target);
+    awareness.step(p, seen, p.hear(pos, shots), dt);
+    awareness.turn(pos, dt);
+}
+
+/// Behaviour and target for `movement`, given the enemy's awareness of the player at
+/// `player` (`None`: don't move).
+///
+/// Engaged enemies (and those without perception) run their behaviour against the
+/// player; suspicious and searching ones walk to the last-known position instead.
+#[inline]
+pub fn pursuit(movement: Movement, awareness: Option<&Awareness>, player: Vec2) -> Option<(Movement, Vec2)> {
+    let Some(awareness) = awareness else { return Some((movement, player)); };
+    match awareness.alert {
+        Alert::Idle => None,
+        Alert::Engaged => Some((movement, player)),
+        Alert::Suspicious { .. } | Alert::Searching { .. } => {
+            let walk = match movement {
+                Movement::Stationary => Movement::Stationary,
+                _ => Movement::Chase { speed: movement.speed(), stop_distance: SEARCH_ARRIVED },
+            };
+            Some((walk, awareness.last_known))
+        }
+    }
+}
+
+/// True when no `Layer::World` collider lies between `from` and `to`.
+fn line_of_sight(spatial: &SpatialQuery, from: Vec2, to: Vec2) -> bool {
+    let Ok(dir) = Dir2::new(to - from) else { return true; };
+    let filter = SpatialQueryFilter::from_mask(Layer::World);
+    spatial.cast_ray(from, dir, from.distance(to), true, &filter).is_none()
+}
+
+/// Enemies with perception, and the emitter their alert state gates.
+type Perceivers<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Position,
+        &'static Perception,
+        &'static EnemyLifeState,
+        &'static mut Awareness,
+        Option<&'static mut Emitter>,
+    ),
+    With<Enemy>,
+>;
+
+/// Update every alive perceiving enemy's `Awareness`; only engaged enemies fire.
+///
+/// Dying enemies are skipped (their emitter is already off).
+pub fn update_perception(
+    time: Res<Time<Fixed>>,
+    spatial: SpatialQuery,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Position, Without<Enemy>>,
+    mut gunshots: MessageReader<Gunshot>,
+    mut shots: Local<Vec<Vec2>>,
+    mut q: Perceivers,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity missing Position").0;
+    let dt = time.delta_secs();
+
+    shots.clear();
+    shots.extend(gunshots.read().map(|shot| shot.pos));
+
+    for (pos, perception, life, mut awareness, emitter) in &mut q {
+        if !matches!(life, EnemyLifeState::Alive) { continue; }
+
+        perceive(&mut awareness, perception, pos.0, target, &shots, dt, |from, to| {
+            line_of_sight(&spatial, from, to)
+        });
+
+        let engaged = matches!(awareness.alert, Alert::Engaged);
+        if let Some(mut emitter) = emitter
+            && emitter.enabled != engaged
+        {
+            emitter.enabled = engaged;
+        }
+    }
+}
@@ -17,18 +17,42 @@
 
 use super::*;
 
+use bevy::ecs::message::Messages;
 use bevy::ecs::system::RunSystemOnce;
 use std::time::{Duration, Instant};
 
+use crate::common::ron_asset::RonAsset;
+use crate::common::rng::SeededRng;
+use crate::common::test_utils::fixed_time_with_delta;
+use crate::plugins::projectiles::cancel::ScorePickup;
+use crate::plugins::combat::status::StatusEffects;
+use crate::plugins::projectiles::components::{Knockback, PlayerEntity, Weight};
+use crate::plugins::projectiles::messages::BulletKind;
+use behaviour::{tick_brains, BehaviourTree, Brain, BrainRng, MoveTarget, Op, Senses, Status};
+use archetype::{spawn_enemy, EnemyArchetype, EnemyArchetypes, EnemyShape, LootDrop, ARCHETYPE_IDS};
+use crate::plugins::world::nav::NavGrid;
+use crowd::{compute_separation, update_crowd_flow, CrowdFlow, Separation, SEPARATION_RADIUS};
+use movement::{apply_enemy_movement, steer, update_nav_paths, Flee, Movement, MovementClock, NavPath};
+use perception::{perceive, pursuit, Alert, Awareness, Perception};
+use waves::{ClearCondition, SpawnGroup, WaveDef, WaveDirector, WavePhase, WaveScript, WaveSignal};
+
 // -----------------------------------------------------------------------------
 // Test utilities
 // -----------------------------------------------------------------------------
 
-/// Helper: create a `Time<Fixed>` with a specific delta for a single system run.
-fn fixed_time_with_delta(dt: f32) -> Time<Fixed> {
-    let mut t = Time::<Fixed>::default();
-    t.advance_by(Duration::from_secs_f32(dt));
-    t
+/// Helper: the `DamageEvent` for a fully armour-blocked hit on `target`.
+fn armour_hit(target: Entity, broken: bool) -> DamageEvent {
+    DamageEvent {
+        source: None,
+        target,
+        amount: 0,
+        damage_type: crate::plugins::combat::DamageType::Kinetic,
+        position: Vec2::ZERO,
+        impulse: Vec2::ZERO,
+        was_armour_hit: true,
+        armour_broken: broken,
+        was_kill: false,
+    }
 }
 
 /// Helper: create a `Time<Real>` and advance by delta using test-friendly API.
@@ -263,6 +287,23 @@ fn globalfx_trigger_armour_break_sets_expected_fields_and_clamps() {
     assert_eq!(fx.trauma.get(), 1.0);
 }
 
+#[test]
+fn globalfx_trigger_explosion_shakes_and_flashes_without_slowmo() {
+    let mut fx = GlobalFx::default();
+
+    fx.trigger_explosion(1.0);
+
+    assert!(fx.trauma.get() > 0.0);
+    assert!(fx.flash.get() > 0.0);
+    assert!(fx.hitstop.get() > 0.0);
+    assert_eq!(fx.slowmo_remaining.get(), 0.0);
+
+    // A weaker blast never dims an active flash.
+    let flash = fx.flash.get();
+    fx.trigger_explosion(0.1);
+    assert!(fx.flash.get() >= flash);
+}
+
 // -----------------------------------------------------------------------------
 // ECS/system tests
 // -----------------------------------------------------------------------------
@@ -270,6 +311,9 @@ fn globalfx_trigger_armour_break_sets_expected_fields_and_clamps() {
 #[test]
 fn enemy_death_trigger_transitions_alive_to_dying_and_disables_collisions() {
     let mut world = World::new();
+    world.init_resource::<Messages<BulletCancel>>();
+    world.init_resource::<RunStats>();
+    world.init_resource::<LootRng>();
 
     // Spawn an enemy with Alive state but hp <= 0.
     // Also: seed sprite colour and non-1 scale so we can verify the system overwrites them.
@@ -302,6 +346,60 @@ fn enemy_death_trigger_transitions_alive_to_dying_and_disables_collisions() {
     // Scale reset.
     let tf = world.get::<Transform>(e).unwrap();
     assert_eq!(tf.scale, Vec3::ONE);
+
+    // One kill scored at the starting multiplier.
+    let stats = world.resource::<RunStats>();
+    assert_eq!((stats.kills, stats.score), (1, KILL_SCORE as u64));
+}
+
+#[test]
+fn enemy_death_trigger_disables_emitter() {
+    let mut world = World::new();
+    world.init_resource::<Messages<BulletCancel>>();
+    world.init_resource::<RunStats>();
+    world.init_resource::<LootRng>();
+    let e = world
+        .spawn((
+            Enemy,
+            Health { hp: 0 },
+            EnemyLifeState::Alive,
+            Sprite::default(),
+            Transform::from_xyz(40.0, -10.0, 1.0),
+            CollisionLayers::new(Layer::Enemy, [Layer::World]),
+            Emitter::new(Handle::default()),
+        ))
+        .id();
+
+    let _ = world.run_system_once(enemy_death_trigger);
+
+    assert!(!world.get::<Emitter>(e).unwrap().enabled);
+
+    // A dying emitter cancels the bullets around it.
+    let msgs = world.resource::<Messages<BulletCancel>>();
+    let cancels: Vec<_> = msgs.iter_current_update_messages().collect();
+    assert_eq!(cancels.len(), 1);
+    assert_eq!(cancels[0].pos, Vec2::new(40.0, -10.0));
+    assert_eq!(cancels[0].radius, DEATH_CANCEL_RADIUS);
+}
+
+#[test]
+fn enemy_death_trigger_without_emitter_cancels_nothing() {
+    let mut world = World::new();
+    world.init_resource::<Messages<BulletCancel>>();
+    world.init_resource::<RunStats>();
+    world.init_resource::<LootRng>();
+    world.spawn((
+        Enemy,
+        Health { hp: 0 },
+        EnemyLifeState::Alive,
+        Sprite::default(),
+        Transform::default(),
+        CollisionLayers::new(Layer::Enemy, [Layer::World]),
+    ));
+
+    let _ = world.run_system_once(enemy_death_trigger);
+
+    assert!(world.resource::<Messages<BulletCancel>>().is_empty());
 }
 
 #[test]
@@ -333,6 +431,7 @@ fn armour_fx_break_triggers_global_fx_and_updates_local_fx() {
 
     world.insert_resource(GlobalFx::default());
     world.insert_resource(fixed_time_with_delta(0.016));
+    world.init_resource::<bevy::ecs::message::Messages<DamageEvent>>();
 
     // Armour drops from 1 -> 0.
     let e = world
@@ -340,11 +439,15 @@ fn armour_fx_break_triggers_global_fx_and_updates_local_fx() {
             Enemy,
             EnemyLifeState::Alive,
             Armour { hits_remaining: 0, max_hits: 1 },
-            ArmourFx::new(1),
+            ArmourFx::new(),
             Sprite::default(),
         ))
         .id();
 
+    world
+        .resource_mut::<bevy::ecs::message::Messages<DamageEvent>>()
+        .write(armour_hit(e, true));
+
     let _ = world.run_system_once(armour_fx_update);
 
     let fx = world.resource::<GlobalFx>();
@@ -357,6 +460,37 @@ fn armour_fx_break_triggers_global_fx_and_updates_local_fx() {
     assert!(local.any_active());
 }
 
+#[test]
+fn armour_fx_flashes_on_armour_hit_without_global_fx_until_break() {
+    let mut world = World::new();
+
+    world.insert_resource(GlobalFx::default());
+    world.insert_resource(fixed_time_with_delta(0.016));
+    world.init_resource::<bevy::ecs::message::Messages<DamageEvent>>();
+
+    let e = world
+        .spawn((
+            Enemy,
+            EnemyLifeState::Alive,
+            Armour { hits_remaining: 2, max_hits: 3 },
+            ArmourFx::new(),
+            Sprite::default(),
+        ))
+        .id();
+
+    // No event: nothing to react to, even though Armour < max.
+    let _ = world.run_system_once(armour_fx_update);
+    assert!(!world.get::<ArmourFx>(e).unwrap().any_active());
+
+    world
+        .resource_mut::<bevy::ecs::message::Messages<DamageEvent>>()
+        .write(armour_hit(e, false));
+    let _ = world.run_system_once(armour_fx_update);
+
+    assert!(world.get::<ArmourFx>(e).unwrap().hit_flash.get() > 0.0);
+    assert_eq!(world.resource::<GlobalFx>().trauma.get(), 0.0);
+}
+
 #[test]
 fn ensure_fx_handles_caches_camera_and_spawns_overlay_when_missing() {
     let mut world = World::new();
@@ -579,4 +713,767 @@ fn hitstop_keeps_speed_zero_until_timer_expires() {
             break;
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn explosion_fx_triggers_preset_per_explosion_message() {
+    let mut world = World::new();
+    world.insert_resource(GlobalFx::default());
+    world.init_resource::<Messages<Explosion>>();
+    world.resource_mut::<Messages<Explosion>>().write(Explosion {
+        kind: BulletKind::Player,
+        owner: None,
+        pos: Vec2::ZERO,
+        radius: EXPLOSION_FX_FULL_RADIUS,
+        damage: 3,
+        damage_type: crate::plugins::combat::DamageType::Explosive,
+        edge_falloff: 0.25,
+        impulse: 0.0,
+        armour_piercing: false,
+    });
+
+    let _ = world.run_system_once(explosion_fx);
+
+    let fx = world.resource::<GlobalFx>();
+    assert!(fx.trauma.get() > 0.0);
+    assert!(fx.flash.get() > 0.0);
+}
+
+#[test]
+fn apply_enemy_knockback_moves_then_settles() {
+    let mut world = movement_world(0.05);
+
+    let mut knockback = Knockback::default();
+    knockback.apply(Vec2::new(300.0, 0.0), Weight(3.0));
+    let e = spawn_mover(&mut world, Movement::Stationary, Vec2::new(100.0, 0.0));
+    world.entity_mut(e).insert(knockback);
+
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::new(100.0, 0.0));
+
+    for _ in 0..20 {
+        let _ = world.run_system_once(apply_enemy_movement);
+    }
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::ZERO);
+}
+
+// -----------------------------------------------------------------------------
+// Movement behaviours
+// -----------------------------------------------------------------------------
+
+/// World with a player at the origin and the fixed clock advanced by `dt`.
+fn movement_world(dt: f32) -> World {
+    let mut world = World::new();
+    world.insert_resource(fixed_time_with_delta(dt));
+    world.init_resource::<CrowdFlow>();
+    let player = world.spawn(Position(Vec2::ZERO)).id();
+    world.insert_resource(PlayerEntity(Some(player)));
+    world
+}
+
+fn spawn_mover(world: &mut World, movement: Movement, pos: Vec2) -> Entity {
+    world
+        .spawn((
+            (Enemy, Health { hp: 5 }, EnemyLifeState::Alive, StatusEffects::default()),
+            movement,
+            MovementClock::default(),
+            NavPath::default(),
+            Separation::default(),
+            Knockback::default(),
+            Position(pos),
+            LinearVelocity::ZERO,
+        ))
+        .id()
+}
+
+#[test]
+fn chase_closes_in_and_stops_short() {
+    let chase = Movement::Chase { speed: 50.0, stop_distance: 40.0 };
+    assert_eq!(steer(chase, Vec2::new(100.0, 0.0), Vec2::ZERO, 0.0), Vec2::new(-50.0, 0.0));
+    assert_eq!(steer(chase, Vec2::new(30.0, 0.0), Vec2::ZERO, 0.0), Vec2::ZERO);
+}
+
+#[test]
+fn strafe_holds_the_band_and_flips_sides() {
+    let strafe = Movement::Strafe { speed: 10.0, min_distance: 50.0, max_distance: 100.0, switch_secs: 2.0 };
+
+    assert_eq!(steer(strafe, Vec2::new(20.0, 0.0), Vec2::ZERO, 0.0), Vec2::new(10.0, 0.0));
+    assert_eq!(steer(strafe, Vec2::new(200.0, 0.0), Vec2::ZERO, 0.0), Vec2::new(-10.0, 0.0));
+
+    // In the band: purely sideways, one way then the other.
+    let first = steer(strafe, Vec2::new(75.0, 0.0), Vec2::ZERO, 0.5);
+    let second = steer(strafe, Vec2::new(75.0, 0.0), Vec2::ZERO, 2.5);
+    assert_eq!(first.x, 0.0);
+    assert_eq!(first.length(), 10.0);
+    assert_eq!(second, -first);
+}
+
+#[test]
+fn orbit_circles_the_player_in_the_requested_direction() {
+    let ccw = Movement::Orbit { speed: 20.0, radius: 100.0, clockwise: false };
+    let cw = Movement::Orbit { speed: 20.0, radius: 100.0, clockwise: true };
+    let on_circle = Vec2::new(100.0, 0.0);
+
+    assert_eq!(steer(ccw, on_circle, Vec2::ZERO, 0.0), Vec2::new(0.0, 20.0));
+    assert_eq!(steer(cw, on_circle, Vec2::ZERO, 0.0), Vec2::new(0.0, -20.0));
+
+    // Off the circle it also heads back toward it.
+    assert!(steer(ccw, Vec2::new(300.0, 0.0), Vec2::ZERO, 0.0).x < 0.0);
+    assert!(steer(ccw, Vec2::new(50.0, 0.0), Vec2::ZERO, 0.0).x > 0.0);
+}
+
+#[test]
+fn flee_overrides_movement_below_the_health_threshold() {
+    let mut world = movement_world(0.05);
+    let e = spawn_mover(&mut world, Movement::Chase { speed: 50.0, stop_distance: 0.0 }, Vec2::new(0.0, 100.0));
+    world.entity_mut(e).insert(Flee { below_hp: 2, speed: 80.0 });
+
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::new(0.0, -50.0));
+
+    world.get_mut::<Health>(e).unwrap().hp = 2;
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::new(0.0, 80.0));
+}
+
+#[test]
+fn frozen_or_dying_enemies_do_not_steer() {
+    let mut world = movement_world(0.05);
+    let chase = Movement::Chase { speed: 50.0, stop_distance: 0.0 };
+    let frozen = spawn_mover(&mut world, chase, Vec2::new(100.0, 0.0));
+    let dying = spawn_mover(&mut world, chase, Vec2::new(-100.0, 0.0));
+
+    world.get_mut::<StatusEffects>(frozen).unwrap().freeze.remaining = 1.0;
+    *world.get_mut::<EnemyLifeState>(dying).unwrap() = EnemyLifeState::Dying {
+        timer: Timer::from_seconds(0.35, TimerMode::Once),
+    };
+
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<LinearVelocity>(frozen).unwrap().0, Vec2::ZERO);
+    assert_eq!(world.get::<LinearVelocity>(dying).unwrap().0, Vec2::ZERO);
+}
+
+/// Stand-in for the physics step: move each body by its velocity.
+fn integrate(time: Res<Time<Fixed>>, mut q: Query<(&mut Position, &LinearVelocity)>) {
+    for (mut pos, vel) in &mut q {
+        pos.0 += vel.0 * time.delta_secs();
+    }
+}
+
+#[test]
+fn orbiting_enemy_settles_on_its_radius_over_fixed_steps() {
+    let mut world = movement_world(1.0 / 64.0);
+    let e = spawn_mover(
+        &mut world,
+        Movement::Orbit { speed: 120.0, radius: 80.0, clockwise: false },
+        Vec2::new(200.0, 0.0),
+    );
+
+    let mut fixed = Schedule::new(FixedPostUpdate);
+    fixed.add_systems((apply_enemy_movement, integrate).chain());
+    for _ in 0..64 * 10 {
+        fixed.run(&mut world);
+    }
+
+    let dist = world.get::<Position>(e).unwrap().0.length();
+    assert!((dist - 80.0).abs() < 5.0, "orbit radius drifted to {dist}");
+    assert!((world.get::<MovementClock>(e).unwrap().0 - 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn chaser_walks_around_a_wall_instead_of_into_it() {
+    let mut world = movement_world(1.0 / 64.0);
+
+    // A wall 3 cells tall between the chaser and the player.
+    let mut grid = NavGrid::new(Rect::new(-320.0, -320.0, 320.0, 320.0), 64.0);
+    grid.set_obstacle(Entity::PLACEHOLDER, Rect::new(-32.0, -96.0, 32.0, 96.0));
+    world.insert_resource(grid);
+
+    let e = spawn_mover(&mut world, Movement::Chase { speed: 100.0, stop_distance: 0.0 }, Vec2::new(-160.0, 0.0));
+    let player = world.resource::<PlayerEntity>().0.unwrap();
+    world.get_mut::<Position>(player).unwrap().0 = Vec2::new(160.0, 0.0);
+
+    let _ = world.run_system_once(update_nav_paths);
+    let path = world.get::<NavPath>(e).unwrap().clone();
+    assert!(path.waypoints.len() > 1);
+    assert_eq!(path.waypoints.last(), Some(&Vec2::new(160.0, 0.0)));
+
+    // First step aims at the first waypoint, off the blocked straight line.
+    let _ = world.run_system_once(apply_enemy_movement);
+    let vel = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!(vel.y.abs() > 1.0, "expected a detour, got {vel}");
+
+    // Moving the player within its cell keeps the path; a grid change recomputes it.
+    world.get_mut::<Position>(player).unwrap().0 = Vec2::new(170.0, 5.0);
+    let _ = world.run_system_once(update_nav_paths);
+    assert_eq!(world.get::<NavPath>(e).unwrap().computed_for, path.computed_for);
+
+    world.resource_mut::<NavGrid>().remove_obstacle(Entity::PLACEHOLDER);
+    let _ = world.run_system_once(update_nav_paths);
+    assert_eq!(world.get::<NavPath>(e).unwrap().waypoints, vec![Vec2::new(170.0, 5.0)]);
+}
+
+// -----------------------------------------------------------------------------
+// Crowd
+// -----------------------------------------------------------------------------
+
+#[test]
+fn close_enemies_push_apart_and_the_dead_are_ignored() {
+    let mut world = movement_world(1.0 / 64.0);
+    let swarm = Movement::Swarm { speed: 60.0 };
+    let a = spawn_mover(&mut world, swarm, Vec2::new(200.0, 0.0));
+    let b = spawn_mover(&mut world, swarm, Vec2::new(210.0, 0.0));
+    let far = spawn_mover(&mut world, swarm, Vec2::new(200.0, SEPARATION_RADIUS * 2.0));
+    let dead = spawn_mover(&mut world, swarm, Vec2::new(200.0, 5.0));
+    *world.get_mut::<EnemyLifeState>(dead).unwrap() = EnemyLifeState::Dead;
+
+    let _ = world.run_system_once(compute_separation);
+
+    let push = |e| world.get::<Separation>(e).unwrap().0;
+    assert!(push(a).x < 0.0 && push(b).x > 0.0);
+    assert_eq!(push(a), -push(b));
+    assert_eq!(push(far), Vec2::ZERO);
+    assert_eq!(push(dead), Vec2::ZERO);
+}
+
+#[test]
+fn enemies_on_the_same_spot_split_in_opposite_directions() {
+    let mut world = movement_world(1.0 / 64.0);
+    let swarm = Movement::Swarm { speed: 60.0 };
+    let a = spawn_mover(&mut world, swarm, Vec2::new(100.0, 100.0));
+    let b = spawn_mover(&mut world, swarm, Vec2::new(100.0, 100.0));
+
+    let _ = world.run_system_once(compute_separation);
+
+    let (pa, pb) = (world.get::<Separation>(a).unwrap().0, world.get::<Separation>(b).unwrap().0);
+    assert!((pa.length() - 1.0).abs() < 1e-5);
+    assert_eq!(pa, -pb);
+}
+
+#[test]
+fn swarmers_follow_the_flow_field_around_walls() {
+    let mut world = movement_world(1.0 / 64.0);
+
+    // Wall between the swarmer and the player, open above.
+    let mut grid = NavGrid::new(Rect::new(-320.0, -320.0, 320.0, 320.0), 64.0);
+    grid.set_obstacle(Entity::PLACEHOLDER, Rect::new(-32.0, -320.0, 32.0, 96.0));
+    world.insert_resource(grid);
+
+    let e = spawn_mover(&mut world, Movement::Swarm { speed: 100.0 }, Vec2::new(-160.0, 0.0));
+    let player = world.resource::<PlayerEntity>().0.unwrap();
+    world.get_mut::<Position>(player).unwrap().0 = Vec2::new(160.0, 0.0);
+
+    let _ = world.run_system_once(update_crowd_flow);
+    assert!(world.resource::<CrowdFlow>().field.goal().is_some());
+
+    let _ = world.run_system_once(apply_enemy_movement);
+    let vel = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!(vel.y > 1.0, "expected to head for the gap above the wall, got {vel}");
+    assert!((vel.length() - 100.0).abs() < 1e-3);
+}
+
+#[test]
+fn separation_never_pushes_a_mover_past_its_speed() {
+    let mut world = movement_world(1.0 / 64.0);
+    let e = spawn_mover(&mut world, Movement::Chase { speed: 50.0, stop_distance: 0.0 }, Vec2::new(100.0, 0.0));
+    world.get_mut::<Separation>(e).unwrap().0 = Vec2::new(-1.0, 0.0);
+
+    let _ = world.run_system_once(apply_enemy_movement);
+    let vel = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!(vel.distance(Vec2::new(-50.0, 0.0)) < 1e-3, "got {vel}");
+
+    // Stationary enemies don't get shoved by their neighbours.
+    let post = spawn_mover(&mut world, Movement::Stationary, Vec2::new(0.0, 100.0));
+    world.get_mut::<Separation>(post).unwrap().0 = Vec2::new(1.0, 0.0);
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<LinearVelocity>(post).unwrap().0, Vec2::ZERO);
+}
+
+// -----------------------------------------------------------------------------
+// Perception
+// -----------------------------------------------------------------------------
+
+fn watcher() -> Perception {
+    Perception { view_range: 300.0, fov_deg: 90.0, hearing_radius: 200.0, suspicious_secs: 1.0, search_secs: 2.0 }
+}
+
+#[test]
+fn view_cone_needs_range_angle_and_a_clear_line() {
+    let p = watcher();
+    assert!(p.in_view_cone(Vec2::ZERO, Vec2::X, Vec2::new(250.0, 0.0)));
+    assert!(p.in_view_cone(Vec2::ZERO, Vec2::X, Vec2::new(100.0, 90.0)), "inside the 45° half-angle");
+    assert!(!p.in_view_cone(Vec2::ZERO, Vec2::X, Vec2::new(100.0, 110.0)), "outside the 45° half-angle");
+    assert!(!p.in_view_cone(Vec2::ZERO, Vec2::X, Vec2::new(350.0, 0.0)), "out of range");
+    assert!(!p.in_view_cone(Vec2::ZERO, Vec2::X, Vec2::new(-50.0, 0.0)), "behind");
+
+    // Line of sight is only asked about a player inside the cone.
+    let mut a = Awareness::facing(Vec2::X);
+    perceive(&mut a, &p, Vec2::ZERO, Vec2::new(-50.0, 0.0), &[], 0.1, |_, _| panic!("asked about a target behind"));
+    assert_eq!(a.alert, Alert::Idle);
+
+    perceive(&mut a, &p, Vec2::ZERO, Vec2::new(100.0, 0.0), &[], 0.1, |_, _| false);
+    assert_eq!(a.alert, Alert::Idle, "a wall hides the player");
+}
+
+#[test]
+fn alert_escalates_on_shots_and_sight_then_decays_to_idle() {
+    let p = watcher();
+    let mut a = Awareness::facing(Vec2::X);
+    let player = Vec2::new(0.0, 100.0);
+
+    // A shot out of sight: suspicious, and turns toward it.
+    perceive(&mut a, &p, Vec2::ZERO, player, &[Vec2::new(0.0, 150.0), Vec2::new(0.0, 250.0)], 0.5, |_, _| true);
+    assert_eq!(a.alert, Alert::Suspicious { remaining: 1.0 });
+    assert_eq!((a.last_known, a.facing), (Vec2::new(0.0, 150.0), Vec2::Y));
+
+    // Now the player is in the cone.
+    perceive(&mut a, &p, Vec2::ZERO, player, &[], 0.5, |_, _| true);
+    assert_eq!(a.alert, Alert::Engaged);
+    assert_eq!(a.last_known, player);
+
+    // Lost behind a wall: search where it was last seen, then give up.
+    perceive(&mut a, &p, Vec2::ZERO, Vec2::new(0.0, 120.0), &[], 0.5, |_, _| false);
+    assert_eq!(a.alert, Alert::Searching { remaining: 2.0 });
+    assert_eq!(a.last_known, player);
+    for _ in 0..3 {
+        perceive(&mut a, &p, Vec2::ZERO, Vec2::new(0.0, 120.0), &[], 0.5, |_, _| false);
+    }
+    assert_eq!(a.alert, Alert::Searching { remaining: 0.5 });
+    perceive(&mut a, &p, Vec2::ZERO, Vec2::new(0.0, 120.0), &[], 0.5, |_, _| false);
+    assert_eq!(a.alert, Alert::Idle);
+
+    // Shots beyond the hearing radius go unnoticed.
+    perceive(&mut a, &p, Vec2::ZERO, Vec2::new(0.0, -100.0), &[Vec2::new(-250.0, 0.0)], 0.5, |_, _| true);
+    assert_eq!(a.alert, Alert::Idle);
+}
+
+#[test]
+fn perceiving_enemies_only_move_on_what_they_know() {
+    let chase = Movement::Chase { speed: 50.0, stop_distance: 0.0 };
+    let mut a = Awareness::facing(Vec2::X);
+    a.last_known = Vec2::new(200.0, 100.0);
+
+    assert_eq!(pursuit(chase, None, Vec2::ZERO), Some((chase, Vec2::ZERO)), "no perception: omniscient");
+    assert_eq!(pursuit(chase, Some(&a), Vec2::ZERO), None);
+
+    // Player at the origin.
+    let mut world = movement_world(1.0 / 64.0);
+    world.insert_resource(NavGrid::default());
+    let e = spawn_mover(&mut world, chase, Vec2::new(200.0, 0.0));
+    world.entity_mut(e).insert((watcher(), a));
+
+    let _ = world.run_system_once(update_nav_paths);
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::ZERO, "idle enemies stay put");
+    assert!(world.get::<NavPath>(e).unwrap().waypoints.is_empty());
+
+    // Searching: walk to the last sighting, not to the player.
+    world.get_mut::<Awareness>(e).unwrap().alert = Alert::Searching { remaining: 1.0 };
+    let _ = world.run_system_once(update_nav_paths);
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<NavPath>(e).unwrap().waypoints.last(), Some(&Vec2::new(200.0, 100.0)));
+    let vel = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!(vel.y > 0.0 && (vel.length() - 50.0).abs() < 1e-3, "got {vel}");
+
+    // Engaged: after the player again.
+    world.get_mut::<Awareness>(e).unwrap().alert = Alert::Engaged;
+    let _ = world.run_system_once(update_nav_paths);
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert_eq!(world.get::<NavPath>(e).unwrap().waypoints.last(), Some(&Vec2::ZERO));
+    let vel = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!(vel.x < -40.0 && (vel.length() - 50.0).abs() < 1e-3, "got {vel}");
+}
+
+// -----------------------------------------------------------------------------
+// Behaviour trees
+// -----------------------------------------------------------------------------
+
+fn tree(src: &str) -> BehaviourTree {
+    BehaviourTree::from_ron_str(src).expect("tree must parse")
+}
+
+fn senses(pos: Vec2) -> Senses {
+    Senses { pos, player: Vec2::ZERO, hp: 5, armour_up: true, has_emitter: true }
+}
+
+/// Tick `brain` `ticks` times at `dt`; the running leaf after each tick.
+fn decisions(tree: &BehaviourTree, senses: &Senses, seed: u64, ticks: usize, dt: f32) -> Vec<Option<usize>> {
+    let mut brain = Brain::default();
+    let mut rng = SeededRng::new(seed);
+    (0..ticks)
+        .map(|_| {
+            tree.tick(&mut brain, senses, &mut rng, dt);
+            brain.active
+        })
+        .collect()
+}
+
+#[test]
+fn behaviour_tree_flattens_in_pre_order() {
+    let t = tree(
+        "Sequence([Wait(secs: 1.0), Selector([CheckArmourUp, Attack(secs: 2.0)]), \
+         MoveTo(to: Point(1.0, 2.0), speed: 50.0, within: 8.0)])",
+    );
+    assert_eq!(t.len(), 6);
+    assert_eq!(t.op(0), &Op::Sequence);
+    assert_eq!(t.op(1), &Op::Wait { secs: 1.0 });
+    assert_eq!(t.op(2), &Op::Selector);
+    assert_eq!(t.op(4), &Op::Attack { secs: 2.0 });
+    assert_eq!(
+        t.op(5),
+        &Op::MoveTo { to: MoveTarget::Point(1.0, 2.0), speed: 50.0, within: 8.0, give_up_secs: 0.0 }
+    );
+}
+
+#[test]
+fn bundled_behaviour_trees_all_parse() {
+    for id in ARCHETYPE_IDS {
+        let path = format!("{}/assets/enemies/{id}.enemy.ron", env!("CARGO_MANIFEST_DIR"));
+        let def = EnemyArchetype::from_ron_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let Some(tree_path) = def.behaviour else { continue; };
+
+        let path = format!("{}/assets/{tree_path}", env!("CARGO_MANIFEST_DIR"));
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+        let tree = BehaviourTree::from_ron_str(&text).unwrap_or_else(|e| panic!("{path}: {e}"));
+        assert!(!tree.is_empty());
+    }
+}
+
+#[test]
+fn sequences_run_in_order_and_selectors_fall_through() {
+    // 0 Selector, 1 Sequence, 2 CheckArmourUp, 3 Attack, 4 Sequence, 5 Wait, 6 Attack
+    let t = tree(
+        "Selector([Sequence([CheckArmourUp, Attack(secs: 1.0)]), \
+         Sequence([Wait(secs: 0.5), Attack(secs: 0.5)])])",
+    );
+
+    // Armour up: the first branch attacks for four ticks, then the tree starts over.
+    assert_eq!(
+        decisions(&t, &senses(Vec2::ZERO), 1, 5, 0.25),
+        [Some(3), Some(3), Some(3), None, Some(3)]
+    );
+
+    // Armour down: the check fails, so wait, attack, and start over.
+    let broken = Senses { armour_up: false, ..senses(Vec2::ZERO) };
+    assert_eq!(decisions(&t, &broken, 1, 4, 0.25), [Some(5), Some(6), None, Some(5)]);
+
+    let mut brain = Brain::default();
+    let mut rng = SeededRng::new(1);
+    assert_eq!(t.tick(&mut brain, &broken, &mut rng, 0.25), Status::Running);
+    assert!(!brain.firing && brain.walk.is_none());
+    assert_eq!(t.tick(&mut brain, &broken, &mut rng, 0.25), Status::Running);
+    assert!(brain.firing);
+    assert_eq!(t.tick(&mut brain, &broken, &mut rng, 0.25), Status::Success);
+    assert!(!brain.firing);
+}
+
+#[test]
+fn health_check_and_missing_emitter_fail_their_branch() {
+    // 0 Selector, 1 Sequence, 2 CheckHealth, 3 Wait, 4 Attack, 5 Wait
+    let t = tree(
+        "Selector([Sequence([CheckHealth(at_most: 2), Wait(secs: 1.0)]), \
+         Attack(secs: 1.0), Wait(secs: 2.0)])",
+    );
+
+    assert_eq!(decisions(&t, &senses(Vec2::ZERO), 1, 1, 0.25), [Some(4)]);
+    assert_eq!(decisions(&t, &Senses { hp: 2, ..senses(Vec2::ZERO) }, 1, 1, 0.25), [Some(3)]);
+    assert_eq!(decisions(&t, &Senses { has_emitter: false, ..senses(Vec2::ZERO) }, 1, 1, 0.25), [Some(5)]);
+}
+
+#[test]
+fn move_to_walks_until_within_range_or_gives_up() {
+    let t = tree("MoveTo(to: Player, speed: 60.0, within: 100.0, give_up_secs: 1.0)");
+    let mut brain = Brain::default();
+    let mut rng = SeededRng::new(1);
+
+    let far = senses(Vec2::new(300.0, 0.0));
+    assert_eq!(t.tick(&mut brain, &far, &mut rng, 0.25), Status::Running);
+    assert_eq!(brain.walk, Some((Movement::Chase { speed: 60.0, stop_distance: 0.0 }, Vec2::ZERO)));
+
+    // The player moved: the walk follows.
+    let moved = Senses { player: Vec2::new(0.0, 50.0), ..far };
+    assert_eq!(t.tick(&mut brain, &moved, &mut rng, 0.25), Status::Running);
+    assert_eq!(brain.walk.map(|(_, goal)| goal), Some(Vec2::new(0.0, 50.0)));
+
+    assert_eq!(t.tick(&mut brain, &senses(Vec2::new(50.0, 0.0)), &mut rng, 0.25), Status::Success);
+    assert_eq!(brain.walk, None);
+
+    // Stuck out of range: fails once the clock reaches give_up_secs.
+    let statuses: Vec<_> = (0..4).map(|_| t.tick(&mut brain, &far, &mut rng, 0.25)).collect();
+    assert_eq!(statuses, [Status::Running, Status::Running, Status::Running, Status::Failure]);
+}
+
+#[test]
+fn random_choices_repeat_for_the_same_seed() {
+    // Leaves 1, 2, 3: Wait (weight 1), Attack (weight 3), never the zero-weight check.
+    let t = tree("RandomChoice([(1.0, Wait(secs: 0.5)), (3.0, Attack(secs: 0.5)), (0.0, CheckArmourUp)])");
+    let run = |seed| decisions(&t, &senses(Vec2::ZERO), seed, 400, 0.25);
+
+    let a = run(7);
+    assert_eq!(a, run(7));
+    assert_ne!(a, run(8));
+
+    let picked = |leaf| a.iter().filter(|d| **d == Some(leaf)).count();
+    assert_eq!(picked(1) + picked(2), 200, "every choice runs for one tick before finishing");
+    assert!(picked(2) > picked(1) * 2, "weights ignored: {} waits, {} attacks", picked(1), picked(2));
+    assert_eq!(picked(3), 0);
+
+    // Wander points come from the same stream.
+    let wander = tree("MoveTo(to: Wander(100.0), speed: 10.0, within: 1.0)");
+    let goal = |seed| {
+        let mut brain = Brain::default();
+        wander.tick(&mut brain, &senses(Vec2::new(50.0, 50.0)), &mut SeededRng::new(seed), 0.25);
+        brain.walk.expect("wander point is away from the start").1
+    };
+    assert_eq!(goal(3), goal(3));
+    assert!(goal(3).distance(Vec2::new(50.0, 50.0)) <= 100.0);
+}
+
+#[test]
+fn brains_drive_movement_and_the_emitter() {
+    let mut world = movement_world(0.25);
+    world.init_resource::<Assets<BehaviourTree>>();
+    world.insert_resource(BrainRng(SeededRng::new(1)));
+    let handle = world
+        .resource_mut::<Assets<BehaviourTree>>()
+        .add(tree("Sequence([MoveTo(to: Point(200.0, 100.0), speed: 40.0, within: 4.0), Attack(secs: 1.0)])"));
+
+    // A chaser by archetype: the brain overrides that.
+    let e = spawn_mover(&mut world, Movement::Chase { speed: 90.0, stop_distance: 0.0 }, Vec2::new(200.0, 0.0));
+    world.entity_mut(e).insert((
+        Armour { hits_remaining: 1, max_hits: 1 },
+        Brain::new(handle),
+        Emitter::new(Handle::default()),
+    ));
+
+    let _ = world.run_system_once(tick_brains);
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert!(!world.get::<Emitter>(e).unwrap().enabled, "holds fire while walking");
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::new(0.0, 40.0));
+
+    world.get_mut::<Position>(e).unwrap().0 = Vec2::new(200.0, 98.0);
+    let _ = world.run_system_once(tick_brains);
+    let _ = world.run_system_once(apply_enemy_movement);
+    assert!(world.get::<Emitter>(e).unwrap().enabled);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::ZERO, "stands still to fire");
+}
+
+// -----------------------------------------------------------------------------
+// Waves
+// -----------------------------------------------------------------------------
+
+fn bundled_waves() -> WaveScript {
+    WaveScript::from_ron_str(include_str!("../../../assets/waves/arena.waves.ron"))
+        .expect("bundled wave script must parse")
+}
+
+#[test]
+fn bundled_wave_script_names_only_known_archetypes() {
+    let script = bundled_waves();
+    assert!(!script.waves.is_empty());
+
+    for group in script.waves.iter().flat_map(|w| &w.groups) {
+        assert!(
+            ARCHETYPE_IDS.contains(&group.archetype.as_str()),
+            "unknown archetype {:?}",
+            group.archetype
+        );
+    }
+}
+
+/// Step the director and record what it asked for, as short strings.
+fn step(director: &mut WaveDirector, script: &WaveScript, dt: f32, alive: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    director.step(script, dt, alive, |signal| {
+        out.push(match signal {
+            WaveSignal::Started(ev) => format!("start {} ({})", ev.index, ev.enemies),
+            WaveSignal::Spawn { wave, archetype, pos } => format!("{wave}:{archetype}@{},{}", pos.x, pos.y),
+            WaveSignal::Cleared(ev) => format!("clear {}{}", ev.index, if ev.last { " last" } else { "" }),
+        });
+    });
+    out
+}
+
+fn two_wave_script() -> WaveScript {
+    WaveScript {
+        waves: vec![
+            WaveDef {
+                delay_secs: 1.0,
+                groups: vec![
+                    SpawnGroup {
+                        archetype: "target".into(),
+                        count: 3,
+                        points: vec![(0.0, 0.0), (10.0, 0.0)],
+                        interval_secs: 0.5,
+                        ..default()
+                    },
+                    SpawnGroup { archetype: "gunner".into(), delay_secs: 0.25, ..default() },
+                ],
+                clear: ClearCondition::AllDead,
+            },
+            WaveDef {
+                delay_secs: 0.5,
+                groups: vec![SpawnGroup::default()],
+                clear: ClearCondition::Timeout(2.0),
+            },
+        ],
+    }
+}
+
+#[test]
+fn director_waits_then_spawns_groups_in_time_order() {
+    let script = two_wave_script();
+    let mut director = WaveDirector::default();
+
+    assert!(step(&mut director, &script, 0.5, 0).is_empty());
+    assert_eq!(step(&mut director, &script, 0.5, 0), ["start 0 (4)", "0:target@0,0"]);
+    assert_eq!(step(&mut director, &script, 0.25, 1), ["0:gunner@0,120"]);
+    assert_eq!(step(&mut director, &script, 0.25, 2), ["0:target@10,0"]);
+    // The last spawn lands; nobody is alive *yet*, but the wave doesn't clear on that step.
+    assert_eq!(step(&mut director, &script, 0.5, 0), ["0:target@0,0"]);
+    assert!(step(&mut director, &script, 0.1, 3).is_empty());
+    assert_eq!(director.phase, WavePhase::Running);
+}
+
+#[test]
+fn wave_clears_when_all_dead_then_next_wave_times_out() {
+    let script = two_wave_script();
+    let mut director = WaveDirector::default();
+    for _ in 0..4 {
+        step(&mut director, &script, 0.5, 1);
+    }
+
+    assert_eq!(step(&mut director, &script, 0.1, 0), ["clear 0"]);
+    assert_eq!((director.wave, director.phase), (1, WavePhase::Waiting));
+
+    assert_eq!(step(&mut director, &script, 0.5, 0), ["start 1 (1)", "1:target@0,120"]);
+    // Timeout: clears with its enemy still alive.
+    assert!(step(&mut director, &script, 1.0, 1).is_empty());
+    assert_eq!(step(&mut director, &script, 1.0, 1), ["clear 1 last"]);
+    assert_eq!(director.phase, WavePhase::Finished);
+    assert!(step(&mut director, &script, 10.0, 0).is_empty());
+}
+
+#[test]
+fn director_finishes_when_the_script_shrinks_under_a_running_wave() {
+    let mut script = two_wave_script();
+    let mut director = WaveDirector::default();
+    for _ in 0..4 {
+        step(&mut director, &script, 0.5, 1);
+    }
+    assert_eq!(director.phase, WavePhase::Running);
+
+    // Hot reload drops the running wave.
+    script.waves.clear();
+    assert!(step(&mut director, &script, 0.1, 0).is_empty());
+    assert_eq!(director.phase, WavePhase::Finished);
+}
+
+// -----------------------------------------------------------------------------
+// Archetypes
+// -----------------------------------------------------------------------------
+
+#[test]
+fn archetype_omitted_fields_use_the_stationary_target() {
+    let def = EnemyArchetype::from_ron_str("(health: 9, shape: Box(width: 20.0, height: 10.0))")
+        .expect("archetype must parse");
+
+    assert_eq!(def.health, 9);
+    assert_eq!(def.shape, EnemyShape::Box { width: 20.0, height: 10.0 });
+    assert_eq!(def.armour, EnemyArchetype::default().armour);
+    assert_eq!(def.movement, Movement::Stationary);
+    assert_eq!((def.emitter, def.score), (None, KILL_SCORE));
+}
+
+#[test]
+fn bundled_archetypes_all_parse() {
+    for id in ARCHETYPE_IDS {
+        let path = format!("{}/assets/enemies/{id}.enemy.ron", env!("CARGO_MANIFEST_DIR"));
+        let text = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+        let def = EnemyArchetype::from_ron_str(&text).unwrap_or_else(|e| panic!("{path}: {e}"));
+        assert!(def.health > 0, "{id}: health must be positive");
+    }
+}
+
+#[test]
+fn spawn_enemy_copies_the_archetype_onto_the_entity() {
+    let mut world = World::new();
+    let mut archetypes = EnemyArchetypes::default();
+    archetypes.insert(
+        "brute",
+        EnemyArchetype {
+            health: 12,
+            armour: 1,
+            weight: 8.0,
+            score: 300,
+            loot: vec![LootDrop::default()],
+            ..default()
+        },
+        None,
+        None,
+    );
+    world.insert_resource(archetypes);
+
+    let spawn = |mut commands: Commands, archetypes: Res<EnemyArchetypes>| {
+        (
+            spawn_enemy(&mut commands, &archetypes, "brute", Vec2::new(5.0, 6.0)),
+            spawn_enemy(&mut commands, &archetypes, "missing", Vec2::ZERO),
+        )
+    };
+    let (brute, missing) = world.run_system_once(spawn).unwrap();
+    assert!(missing.is_none());
+
+    let e = brute.expect("known archetype spawns");
+    assert_eq!(world.get::<Health>(e).unwrap().hp, 12);
+    assert_eq!(world.get::<Armour>(e).unwrap().max_hits, 1);
+    assert_eq!(world.get::<Weight>(e).unwrap().0, 8.0);
+    assert_eq!(world.get::<ScoreValue>(e).unwrap().0, 300);
+    assert_eq!(world.get::<LootTable>(e).unwrap().0.len(), 1);
+    assert_eq!(world.get::<Transform>(e).unwrap().translation.truncate(), Vec2::new(5.0, 6.0));
+    assert!(world.get::<Emitter>(e).is_none());
+    assert!(world.get::<Awareness>(e).is_none(), "no perception unless the archetype has one");
+}
+
+#[test]
+fn loot_rolls_repeat_for_the_same_seed() {
+    let table = LootTable(vec![
+        LootDrop { chance: 0.5, value: 10, count: 1 },
+        LootDrop { chance: 0.5, value: 20, count: 3 },
+    ]);
+    let roll_all = |seed| {
+        let mut rng = SeededRng::new(seed);
+        let mut drops = Vec::new();
+        for _ in 0..32 {
+            table.roll(&mut rng, Vec2::ZERO, |_, value| drops.push(value));
+        }
+        drops
+    };
+
+    let drops = roll_all(7);
+    assert_eq!(drops, roll_all(7));
+    assert!(drops.contains(&10) && drops.contains(&20));
+    assert!(drops.len() < 32 * 4, "50% drops should miss at least once in 32 rolls");
+}
+
+#[test]
+fn enemy_death_trigger_scores_the_archetype_and_drops_loot() {
+    let mut world = World::new();
+    world.init_resource::<Messages<BulletCancel>>();
+    world.init_resource::<RunStats>();
+    world.init_resource::<LootRng>();
+    world.spawn((
+        (Enemy, Health { hp: 0 }, EnemyLifeState::Alive),
+        Sprite::default(),
+        Transform::from_xyz(30.0, 0.0, 1.0),
+        CollisionLayers::new(Layer::Enemy, [Layer::World]),
+        ScoreValue(250),
+        LootTable(vec![LootDrop { chance: 1.0, value: 20, count: 3 }]),
+    ));
+
+    let _ = world.run_system_once(enemy_death_trigger);
+
+    assert_eq!(world.resource::<RunStats>().score, 250);
+    let mut pickups = world.query::<&ScorePickup>();
+    let values: Vec<_> = pickups.iter(&world).map(|p| p.value).collect();
+    assert_eq!(values, vec![20, 20, 20]);
+}
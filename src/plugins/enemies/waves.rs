@@ -0,0 +1,284 @@
+//! Wave director: spawns enemies over time from a wave script file.
+//!
+//! # Data flow
+//! ```text
+//!   assets/waves/*.waves.ron ──(RonAssetLoader)──> Assets<WaveScript>
+//!   OnEnter(InGame): start_waves (load the arena script, reset WaveDirector)
+//!   FixedUpdate: run_wave_director
+//!       WaveDirector::step ──> archetype::spawn_enemy (id, position) + WaveMember
+//!                          ──> WaveStarted / WaveCleared messages
+//! ```
+//!
+//! # Wave lifecycle
+//! ```text
+//!   Waiting { delay_secs } ──> Running (WaveStarted; spawn queue built from the groups)
+//!   Running ──(queue empty + clear condition)──> WaveCleared ──> next wave's Waiting
+//!   last wave cleared ──> Finished
+//! ```
+//! - `AllDead`: every enemy of the wave has left `EnemyLifeState::Alive`.
+//! - `Timeout(secs)`: the wave clears `secs` after it started, whatever is still alive.
+//! - `AllDeadOrTimeout(secs)`: whichever comes first.
+//!
+//! # Determinism
+//! The director steps on `Time<Fixed>` with no RNG: a script always spawns the same
+//! enemies at the same ticks. It holds until every archetype file has loaded. `step`
+//! takes its side effects as a closure, so the rules are testable without an asset
+//! server or a physics world.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::common::ron_asset::RonAsset;
+
+use super::archetype::{spawn_enemy, EnemyArchetypes};
+use super::EnemyLifeState;
+
+/// Script loaded on entering `InGame`.
+pub const ARENA_WAVES: &str = "waves/arena.waves.ron";
+
+/// When a running wave counts as cleared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum ClearCondition {
+    #[default]
+    AllDead,
+    Timeout(f32),
+    AllDeadOrTimeout(f32),
+}
+
+/// `count` enemies of one archetype, spawned `interval_secs` apart.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SpawnGroup {
+    /// Archetype id (see `archetype::ARCHETYPE_IDS`).
+    pub archetype: String,
+    pub count: u16,
+    /// Spawn positions, used in turn (enemy `i` spawns at `points[i % len]`).
+    pub points: Vec<(f32, f32)>,
+    /// Seconds after the wave starts before the first enemy of the group.
+    pub delay_secs: f32,
+    pub interval_secs: f32,
+}
+
+impl Default for SpawnGroup {
+    fn default() -> Self {
+        Self {
+            archetype: "target".into(),
+            count: 1,
+            points: vec![(0.0, 120.0)],
+            delay_secs: 0.0,
+            interval_secs: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WaveDef {
+    /// Seconds before the wave starts (counted from the previous wave's clear).
+    pub delay_secs: f32,
+    pub groups: Vec<SpawnGroup>,
+    pub clear: ClearCondition,
+}
+
+/// Designer-facing wave script: waves run in order, once each.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct WaveScript {
+    pub waves: Vec<WaveDef>,
+}
+
+impl RonAsset for WaveScript {
+    const EXTENSIONS: &'static [&'static str] = &["waves.ron"];
+}
+
+/// A wave has started spawning.
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct WaveStarted {
+    pub index: u32,
+    /// Enemies the wave will spawn in total.
+    pub enemies: u32,
+}
+
+/// A wave met its clear condition.
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct WaveCleared {
+    pub index: u32,
+    /// No waves left in the script.
+    pub last: bool,
+}
+
+/// Tag on every enemy spawned by the director.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WaveMember {
+    pub wave: u32,
+}
+
+/// One enemy still to spawn in the running wave.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingSpawn {
+    /// Seconds after the wave start.
+    pub at: f32,
+    pub archetype: String,
+    pub pos: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WavePhase {
+    #[default]
+    Waiting,
+    Running,
+    Finished,
+}
+
+/// Side effect requested by `WaveDirector::step`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WaveSignal<'a> {
+    Started(WaveStarted),
+    Spawn { wave: u32, archetype: &'a str, pos: Vec2 },
+    Cleared(WaveCleared),
+}
+
+/// Runtime position in the wave script.
+#[derive(Resource, Debug, Default)]
+pub struct WaveDirector {
+    pub script: Handle<WaveScript>,
+    pub wave: usize,
+    pub phase: WavePhase,
+    /// Seconds of fixed time in the current phase.
+    pub elapsed: f32,
+    /// Spawns left in the running wave, in spawn order.
+    pub pending: Vec<PendingSpawn>,
+}
+
+impl WaveDirector {
+    pub fn new(script: Handle<WaveScript>) -> Self {
+        Self { script, ..default() }
+    }
+
+    /// Advance by `dt`. `alive` is the number of the current wave's enemies still alive.
+    ///
+    /// An enemy spawned in this step is not counted in `alive` yet, so a wave never
+    /// clears on the step that spawns its last enemy.
+    pub fn step(
+        &mut self,
+        script: &WaveScript,
+        dt: f32,
+        alive: usize,
+        mut signal: impl FnMut(WaveSignal<'_>),
+    ) {
+        self.elapsed += dt;
+
+        if self.phase == WavePhase::Waiting {
+            let Some(def) = script.waves.get(self.wave) else {
+                self.phase = WavePhase::Finished;
+                return;
+            };
+            if self.elapsed < def.delay_secs { return; }
+
+            self.elapsed -= def.delay_secs;
+            self.pending = spawn_queue(def);
+            self.phase = WavePhase::Running;
+            signal(WaveSignal::Started(WaveStarted {
+                index: self.wave as u32,
+                enemies: self.pending.len() as u32,
+            }));
+        }
+
+        if self.phase != WavePhase::Running { return; }
+
+        let due = self.pending.iter().take_while(|p| p.at <= self.elapsed).count();
+        for p in self.pending.drain(..due) {
+            signal(WaveSignal::Spawn { wave: self.wave as u32, archetype: &p.archetype, pos: p.pos });
+        }
+        if due > 0 { return; }
+
+        // The script can shrink under a running wave (hot reload).
+        let Some(def) = script.waves.get(self.wave) else {
+            self.phase = WavePhase::Finished;
+            return;
+        };
+        let all_dead = alive == 0 && self.pending.is_empty();
+        let timed_out = |secs: f32| self.elapsed >= secs;
+        let cleared = match def.clear {
+            ClearCondition::AllDead => all_dead,
+            ClearCondition::Timeout(secs) => timed_out(secs),
+            ClearCondition::AllDeadOrTimeout(secs) => all_dead || timed_out(secs),
+        };
+        if !cleared { return; }
+
+        let last = self.wave + 1 >= script.waves.len();
+        signal(WaveSignal::Cleared(WaveCleared { index: self.wave as u32, last }));
+
+        self.pending.clear();
+        self.elapsed = 0.0;
+        self.wave += 1;
+        self.phase = if last { WavePhase::Finished } else { WavePhase::Waiting };
+    }
+}
+
+/// Every spawn of a wave, sorted by time (stable: groups keep file order on ties).
+fn spawn_queue(def: &WaveDef) -> Vec<PendingSpawn> {
+    let mut queue: Vec<PendingSpawn> = def
+        .groups
+        .iter()
+        .filter(|g| !g.points.is_empty())
+        .flat_map(|g| {
+            (0..g.count).map(move |i| {
+                let (x, y) = g.points[i as usize % g.points.len()];
+                PendingSpawn {
+                    at: g.delay_secs + g.interval_secs * i as f32,
+                    archetype: g.archetype.clone(),
+                    pos: Vec2::new(x, y),
+                }
+            })
+        })
+        .collect();
+    queue.sort_by(|a, b| a.at.total_cmp(&b.at));
+    queue
+}
+
+pub fn start_waves(asset_server: Res<AssetServer>, mut director: ResMut<WaveDirector>) {
+    *director = WaveDirector::new(asset_server.load(ARENA_WAVES));
+}
+
+/// Wave lifecycle messages written by the director.
+#[derive(SystemParam)]
+pub struct WaveMessages<'w> {
+    started: MessageWriter<'w, WaveStarted>,
+    cleared: MessageWriter<'w, WaveCleared>,
+}
+
+pub fn run_wave_director(
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+    archetypes: Res<EnemyArchetypes>,
+    scripts: Res<Assets<WaveScript>>,
+    mut director: ResMut<WaveDirector>,
+    q_members: Query<(&WaveMember, &EnemyLifeState)>,
+    mut messages: WaveMessages,
+) {
+    // Not loaded yet: the run starts once the script and the archetypes are.
+    let Some(script) = scripts.get(&director.script) else { return; };
+    if !archetypes.is_ready() { return; }
+
+    let current = director.wave as u32;
+    let alive = q_members
+        .iter()
+        .filter(|(m, life)| m.wave == current && matches!(life, EnemyLifeState::Alive))
+        .count();
+
+    director.step(script, time.delta_secs(), alive, |signal| match signal {
+        WaveSignal::Started(ev) => {
+            messages.started.write(ev);
+        }
+        WaveSignal::Spawn { wave, archetype, pos } => {
+            if let Some(e) = spawn_enemy(&mut commands, &archetypes, archetype, pos) {
+                commands.entity(e).insert(WaveMember { wave });
+            }
+        }
+        WaveSignal::Cleared(ev) => {
+            messages.cleared.write(ev);
+        }
+    });
+}
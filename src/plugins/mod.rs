@@ -4,11 +4,13 @@ use bevy::prelude::*;
 
 use crate::plugins::{projectiles::ProjectilesPlugin, ui::debug_hud};
 
+pub mod combat;
 pub mod core;
 pub mod enemies;
 pub mod physics;
 pub mod player;
 pub mod projectiles;
+pub mod score;
 pub mod ui;
 pub mod world;
 
@@ -22,7 +24,9 @@ pub fn register_gameplay(app: &mut App) {
     physics::plugin(app);
     world::plugin(app);
     player::plugin(app);
+    combat::plugin(app);
     enemies::plugin(app);
+    score::plugin(app);
     debug_hud::plugin(app);
     app.add_plugins(ProjectilesPlugin);
 }
@@ -8,7 +8,9 @@
 //! ```text
 //!   OnEnter(InGame): spawn player entity -> write PlayerEntity resource
 //!   PreUpdate:       gather input -> PlayerInput
-//!   FixedPostUpdate: apply movement -> Query::get_mut(PlayerEntity)
+//!   FixedPostUpdate: apply movement -> Query::get_mut(PlayerEntity), scaled by StatusEffects,
+//!                    blended with Knockback
+//!   FixedPostUpdate: tick i-frames  -> before enemy bullet resolve
 //! ```
 
 use avian2d::prelude::*;
@@ -17,12 +19,22 @@ use bevy::state::state_scoped::DespawnOnExit;
 
 use crate::{
     common::{state::GameState, tunables::Tunables},
+    plugins::combat::status::StatusEffects,
     plugins::projectiles::{
-        components::{Player, PlayerEntity},
+        collision::process_enemy_bullet_collisions,
+        components::{Health, Invulnerability, Knockback, Player, PlayerEntity, Weight},
+        graze::GrazeZone,
         layers::Layer,
+        weapon::{Weapon, WeaponDef, WeaponState},
     },
 };
 
+const PLAYER_MAX_HP: i32 = 10;
+const PLAYER_WEIGHT: f32 = 1.0;
+const PLAYER_RADIUS: f32 = 13.0;
+/// Enemy bullets overlapping this circle around the player without hitting are grazes.
+const PLAYER_GRAZE_RADIUS: f32 = 40.0;
+
 #[derive(Resource, Default, Debug)]
 struct PlayerInput {
     move_axis: Vec2,
@@ -37,6 +49,12 @@ pub fn plugin(app: &mut App) {
             apply_movement
                 .before(PhysicsSystems::StepSimulation)
                 .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            tick_invulnerability
+                .before(process_enemy_bullet_collisions)
+                .run_if(in_state(GameState::InGame)),
         );
 }
 
@@ -48,8 +66,18 @@ fn spawn(mut commands: Commands) {
 
     let e = commands
         .spawn((
-            Name::new("Player"),
-            Player,
+            (
+                Name::new("Player"),
+                Player,
+                Health { hp: PLAYER_MAX_HP },
+                Invulnerability::default(),
+                Knockback::default(),
+                Weight(PLAYER_WEIGHT),
+                StatusEffects::default(),
+                Weapon::default(),
+                WeaponState::full(&WeaponDef::default()),
+                GrazeZone { radius: PLAYER_GRAZE_RADIUS, hurt_radius: PLAYER_RADIUS },
+            ),
             Sprite {
                 color: Color::srgb(0.2, 0.75, 0.9),
                 custom_size: Some(Vec2::splat(26.0)),
@@ -57,7 +85,7 @@ fn spawn(mut commands: Commands) {
             },
             Transform::from_xyz(0.0, 0.0, 1.0),
             RigidBody::Dynamic,
-            Collider::circle(13.0),
+            Collider::circle(PLAYER_RADIUS),
             layers,
             LockedAxes::ROTATION_LOCKED,
             Restitution::ZERO,
@@ -89,15 +117,33 @@ fn gather_input(keys: Option<Res<ButtonInput<KeyCode>>>, mut input: ResMut<Playe
     };
 }
 
+/// Drive the player from input.
+///
+/// Outside a knockback this is a plain overwrite; while stunned the input is blended
+/// with the knockback velocity so hits visibly shove the player.
 fn apply_movement(
+    time: Res<Time<Fixed>>,
     tunables: Res<Tunables>,
     input: Res<PlayerInput>,
     player_e: Res<PlayerEntity>,
-    mut q_vel: Query<&mut LinearVelocity>,
+    mut q_vel: Query<(&mut LinearVelocity, &mut Knockback, &StatusEffects)>,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let (mut vel, mut knockback, status) = q_vel.get_mut(player).expect("PlayerEntity invalid");
+    let speed = tunables.player_speed * status.speed_mult();
+    vel.0 = knockback.blend(input.move_axis * speed);
+    knockback.tick(time.delta_secs());
+}
+
+/// Count down the i-frame window on fixed (virtual-driven) time.
+fn tick_invulnerability(
+    time: Res<Time<Fixed>>,
+    player_e: Res<PlayerEntity>,
+    mut q: Query<&mut Invulnerability>,
 ) {
     let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
-    let mut vel = q_vel.get_mut(player).expect("PlayerEntity invalid");
-    vel.0 = input.move_axis * tunables.player_speed;
+    let mut iframes = q.get_mut(player).expect("PlayerEntity invalid");
+    iframes.tick(time.delta_secs());
 }
 
 #[cfg(test)]
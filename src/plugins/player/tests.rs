@@ -1,8 +1,9 @@
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
-use crate::common::test_utils::run_system_once;
+use crate::common::test_utils::{fixed_time_with_delta, run_system_once};
 use crate::common::tunables::Tunables;
+use crate::plugins::combat::status::{StatusApply, StatusEffects, StatusKind};
 
 #[test]
 fn spawn_creates_player() {
@@ -28,7 +29,16 @@ fn apply_movement_sets_velocity() {
     world.insert_resource(super::PlayerInput {
         move_axis: Vec2::new(1.0, 0.0),
     });
-    world.spawn((super::Player, LinearVelocity::ZERO));
+    world.insert_resource(Time::<Fixed>::default());
+    let e = world
+        .spawn((
+            super::Player,
+            LinearVelocity::ZERO,
+            super::Knockback::default(),
+            StatusEffects::default(),
+        ))
+        .id();
+    world.insert_resource(super::PlayerEntity(Some(e)));
 
     run_system_once(&mut world, super::apply_movement);
 
@@ -113,3 +123,100 @@ fn movement_runs_before_physics_step_simulation() {
     let observed = app.world().resource::<ObservedVel>().0;
     assert_eq!(observed, Some(Vec2::new(100.0, 0.0)));
 }
+
+#[test]
+fn spawn_gives_player_health_and_iframes() {
+    let mut world = World::new();
+    run_system_once(&mut world, super::spawn);
+
+    let (hp, iframes) = world
+        .query_filtered::<(&super::Health, &super::Invulnerability), With<super::Player>>()
+        .single(&world)
+        .unwrap();
+    assert_eq!(hp.hp, super::PLAYER_MAX_HP);
+    assert!(!iframes.is_active());
+}
+
+#[test]
+fn tick_invulnerability_counts_down_on_fixed_time() {
+    let mut world = World::new();
+    world.insert_resource(fixed_time_with_delta(0.5));
+
+    let e = world
+        .spawn((super::Player, super::Invulnerability { remaining: 0.75 }))
+        .id();
+    world.insert_resource(super::PlayerEntity(Some(e)));
+
+    run_system_once(&mut world, super::tick_invulnerability);
+    let remaining = world.get::<super::Invulnerability>(e).unwrap().remaining;
+    assert!((remaining - 0.25).abs() < 1e-4);
+
+    run_system_once(&mut world, super::tick_invulnerability);
+    assert!(!world.get::<super::Invulnerability>(e).unwrap().is_active());
+}
+
+#[test]
+fn apply_movement_blends_input_with_knockback_while_stunned() {
+    let mut world = World::new();
+    world.insert_resource(Tunables {
+        pixels_per_meter: 20.0,
+        player_speed: 100.0,
+        bullet_speed: 0.0,
+    });
+    world.insert_resource(super::PlayerInput {
+        move_axis: Vec2::new(1.0, 0.0),
+    });
+    world.insert_resource(fixed_time_with_delta(0.05));
+
+    let mut knockback = super::Knockback::default();
+    knockback.apply(Vec2::new(0.0, 300.0), super::Weight(2.0));
+    let e = world
+        .spawn((super::Player, LinearVelocity::ZERO, knockback, StatusEffects::default()))
+        .id();
+    world.insert_resource(super::PlayerEntity(Some(e)));
+
+    run_system_once(&mut world, super::apply_movement);
+
+    // Just hit: no input control yet, knockback = impulse / weight.
+    let v = world.get::<LinearVelocity>(e).unwrap().0;
+    assert_eq!(v, Vec2::new(0.0, 150.0));
+
+    // The window closes and the knockback decays: input takes over again.
+    for _ in 0..20 {
+        run_system_once(&mut world, super::apply_movement);
+    }
+    let v = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!((v - Vec2::new(100.0, 0.0)).length() < 1.0, "{v:?}");
+}
+
+#[test]
+fn apply_movement_scales_input_by_status_speed() {
+    let mut world = World::new();
+    world.insert_resource(Tunables {
+        pixels_per_meter: 20.0,
+        player_speed: 100.0,
+        bullet_speed: 0.0,
+    });
+    world.insert_resource(super::PlayerInput {
+        move_axis: Vec2::new(1.0, 0.0),
+    });
+    world.insert_resource(Time::<Fixed>::default());
+
+    let mut status = StatusEffects::default();
+    status.apply(&StatusApply { kind: StatusKind::Slow, duration_secs: 1.0, potency: 0.25 });
+    let e = world
+        .spawn((super::Player, LinearVelocity::ZERO, super::Knockback::default(), status))
+        .id();
+    world.insert_resource(super::PlayerEntity(Some(e)));
+
+    run_system_once(&mut world, super::apply_movement);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::new(75.0, 0.0));
+
+    world.get_mut::<StatusEffects>(e).unwrap().apply(&StatusApply {
+        kind: StatusKind::Freeze,
+        duration_secs: 1.0,
+        potency: 0.0,
+    });
+    run_system_once(&mut world, super::apply_movement);
+    assert_eq!(world.get::<LinearVelocity>(e).unwrap().0, Vec2::ZERO);
+}
@@ -3,48 +3,130 @@
 //! # Fail-fast invariants
 //! - The pool free list contains only valid pooled bullet entities.
 //! - Therefore, a pooled entity must match the bullet query.
+//!   The one exception is entities grown during *this* run: their spawn commands are not
+//!   applied yet, so they are activated through `Commands` instead.
 //!
 //! If this is violated, we `expect()` and crash loudly.
 //! This removes branches from the hot loop and makes invariant violations obvious.
+//!
+//! # Capacity
+//! An empty free list is a capacity decision, not a correctness failure: the pool's
+//! `OverflowPolicy` decides between dropping, recycling the oldest bullet, or growing.
+//!
+//! # Catch-up
+//! Emitter volleys are due at fixed-step times but only allocated here, once per frame.
+//! Each such bullet starts where it would be had it spawned on time
+//! (`SpawnBulletRequest::caught_up`), so patterns don't bunch at low frame rates.
 
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy::ecs::message::MessageReader;
 
-use super::components::{Bullet, BulletEntity, BulletState, PooledBullet};
-use super::messages::{BulletKind, SpawnBulletRequest};
-use super::pool::{active_enemy_layers, active_player_layers, BulletPool};
+use super::components::{
+    Bullet, BulletEntity, BulletState, CollisionStamp, HitTargets, PooledBullet,
+};
+use super::graze::GrazeMark;
+use super::messages::SpawnBulletRequest;
+use super::pool::{active_layers, pooled_bullet_bundle, BulletPools, OverflowPolicy};
+
+/// Component values of a freshly fired bullet.
+///
+/// Single definition shared by the in-place path (query writes) and the grow path
+/// (command insert), so both activate bullets identically.
+fn fired(
+    req: &SpawnBulletRequest,
+    activation: u32,
+    mut bullet: Bullet,
+    stamp: CollisionStamp,
+) -> (BulletState, Bullet, CollisionStamp, Transform, LinearVelocity, Visibility, CollisionLayers) {
+    bullet.reset_for_fire(req, activation);
+    let layers = active_layers(&bullet);
+    (
+        BulletState::Active,
+        bullet,
+        // Keep the epoch (monotonic); forget the previous life's pierce targets and graze.
+        CollisionStamp { hit: HitTargets::default(), graze: GrazeMark::Clear, ..stamp },
+        // Scale sizes the sprite and (via Avian's transform sync) the collider per shot.
+        Transform::from_translation(req.pos.extend(2.0)).with_scale(Vec3::splat(req.scale)),
+        LinearVelocity(req.vel),
+        Visibility::Visible,
+        layers,
+    )
+}
 
 pub fn allocate_bullets_from_pool(
-    mut pool: ResMut<BulletPool>,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+    mut pools: ResMut<BulletPools>,
     mut reader: MessageReader<SpawnBulletRequest>,
     mut q: Query<(
+        Entity,
         &mut BulletState,
         &mut Bullet,
+        &mut CollisionStamp,
         &mut Transform,
         &mut LinearVelocity,
         &mut Visibility,
         &mut CollisionLayers,
     ), With<PooledBullet>>,
+    mut grown: Local<Vec<Entity>>,
 ) {
+    grown.clear();
+
+    let now = time.elapsed_secs();
+
     for req in reader.read() {
-        let Some(BulletEntity(e)) = pool.pop_free() else {
-            // Capacity decision, not a correctness failure.
-            continue;
-        };
+        let req = req.caught_up(now);
+        let activation = pools.next_activation();
+        let pool = pools.get_mut(req.kind);
 
-        let (mut state, mut bullet, mut tf, mut vel, mut vis, mut layers) =
-            q.get_mut(e).expect("BulletPool contained an entity missing pooled bullet components");
+        let e = match pool.pop_free() {
+            Some(BulletEntity(e)) => e,
+            None => match pool.policy {
+                OverflowPolicy::DropNewest => continue,
+                OverflowPolicy::RecycleOldest => {
+                    let oldest = q
+                        .iter()
+                        .filter(|(_, state, bullet, ..)| {
+                            **state == BulletState::Active && bullet.kind == req.kind
+                        })
+                        .min_by_key(|(_, _, bullet, ..)| bullet.activation)
+                        .map(|(e, ..)| e);
+                    let Some(e) = oldest else { continue; };
+                    e
+                }
+                OverflowPolicy::Grow { .. } => {
+                    let n = pool.grow_amount();
+                    // Ceiling reached: behave like DropNewest.
+                    if n == 0 { continue; }
 
-        *state = BulletState::Active;
-        bullet.reset_for_fire(req.damage);
-        tf.translation = req.pos.extend(2.0);
-        vel.0 = req.vel;
-        *vis = Visibility::Visible;
+                    for _ in 0..n {
+                        let e = commands.spawn(pooled_bullet_bundle(pool.kind)).id();
+                        grown.push(e);
+                        pool.push_free(BulletEntity(e));
+                    }
+                    pool.capacity += n;
 
-        *layers = match req.kind {
-            BulletKind::Player => active_player_layers(),
-            BulletKind::Enemy => active_enemy_layers(),
+                    let Some(BulletEntity(e)) = pool.pop_free() else { unreachable!() };
+                    e
+                }
+            },
         };
+
+        if grown.contains(&e) {
+            commands.entity(e).insert(fired(
+                &req,
+                activation,
+                Bullet::pooled(req.kind),
+                CollisionStamp::default(),
+            ));
+            continue;
+        }
+
+        let (_, mut state, mut bullet, mut stamp, mut tf, mut vel, mut vis, mut layers) =
+            q.get_mut(e).expect("BulletPool contained an entity missing pooled bullet components");
+
+        let prev = bullet.clone();
+        (*state, *bullet, *stamp, *tf, *vel, *vis, *layers) = fired(&req, activation, prev, *stamp);
     }
 }
@@ -0,0 +1,96 @@
+//! Bullet cancel: convert enemy bullets into score pickups.
+//!
+//! ```text
+//!   BulletCancel { pos, radius } (e.g. an emitter enemy dying)
+//!   apply_bullet_cancels (fixed step, after detonation, before the return commit)
+//!       active enemy bullets in radius ──> PendingReturn + ScorePickup entity
+//!   collect_score_pickups (fixed step)
+//!       pickups drift to the player ──> ScoreCollected message
+//! ```
+//!
+//! Cancelled bullets go back through `return_to_pool_commit` like any other return.
+//! Running after `detonate_explosives` means a cancelled explosive simply fizzles.
+//!
+//! # Pickups
+//! Pickups are plain entities rather than pooled: cancels are rare, and a pickup lives
+//! for well under a second before the magnet collects it.
+
+use bevy::prelude::*;
+use bevy::state::state_scoped::DespawnOnExit;
+use avian2d::prelude::*;
+
+use crate::common::state::GameState;
+
+use super::components::{Bullet, BulletState, Player, PlayerEntity, PooledBullet};
+use super::messages::{BulletCancel, BulletKind, ScoreCollected};
+
+/// Score for one cancelled bullet.
+pub const PICKUP_VALUE: u32 = 10;
+/// Speed at which pickups home in on the player (px/s).
+pub const PICKUP_MAGNET_SPEED: f32 = 700.0;
+/// Pickups closer than this to the player are collected.
+pub const PICKUP_COLLECT_RADIUS: f32 = 18.0;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ScorePickup {
+    pub value: u32,
+}
+
+/// A score pickup at `pos` (cancelled bullets, enemy loot).
+pub fn score_pickup_bundle(pos: Vec2, value: u32) -> impl Bundle {
+    (
+        Name::new("ScorePickup"),
+        ScorePickup { value },
+        Sprite {
+            color: Color::srgb(1.0, 0.9, 0.2),
+            custom_size: Some(Vec2::splat(6.0)),
+            ..default()
+        },
+        Transform::from_translation(pos.extend(3.0)),
+        DespawnOnExit(GameState::InGame),
+    )
+}
+
+/// Return active enemy bullets inside each cancel radius and drop a pickup where each was.
+pub fn apply_bullet_cancels(
+    mut commands: Commands,
+    mut cancels: MessageReader<BulletCancel>,
+    mut q: Query<(&Bullet, &mut BulletState, &Position), With<PooledBullet>>,
+) {
+    for cancel in cancels.read() {
+        let radius_sq = cancel.radius * cancel.radius;
+
+        for (bullet, mut state, pos) in &mut q {
+            if bullet.kind != BulletKind::Enemy || *state != BulletState::Active { continue; }
+            if pos.0.distance_squared(cancel.pos) > radius_sq { continue; }
+
+            *state = BulletState::PendingReturn;
+            commands.spawn(score_pickup_bundle(pos.0, PICKUP_VALUE));
+        }
+    }
+}
+
+/// Pull pickups toward the player and collect the ones that arrive.
+pub fn collect_score_pickups(
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Transform, With<Player>>,
+    mut q_pickups: Query<(Entity, &mut Transform, &ScorePickup), Without<Player>>,
+    mut collected: MessageWriter<ScoreCollected>,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity invalid").translation.truncate();
+    let step = PICKUP_MAGNET_SPEED * time.delta_secs();
+
+    for (e, mut tf, pickup) in &mut q_pickups {
+        let pos = tf.translation.truncate();
+        let next = pos + (target - pos).clamp_length_max(step);
+        tf.translation = next.extend(tf.translation.z);
+
+        if next.distance(target) <= PICKUP_COLLECT_RADIUS {
+            collected.write(ScoreCollected { value: pickup.value, pos: next });
+            commands.entity(e).despawn();
+        }
+    }
+}
@@ -1,18 +1,60 @@
-//! Collision resolve: apply gameplay rules and mark bullets for return.
+//! Collision resolve: turn contacts into damage requests and mark bullets for return.
 //!
 //! # Hot path design
 //! - No HashSet dedupe: we use `CollisionStamp` + `CollisionEpoch`.
 //! - Fail-fast for impossible states: if a collider is a pooled bullet, it must have bullet data.
+//! - No direct `Armour`/`Health` writes: hits become `DamageRequest`s, and the combat
+//!   plugin's `apply_damage` owns armour, health, i-frames and knockback rules.
 //!
 //! # Rule summary
-//! - World: decrement wall bounce budget; at 0 => PendingReturn
-//! - Enemy: armour gate; if armour up => wear; else apply damage and PendingReturn
+//! - World: if ricochet budget left => reflect velocity off the wall face; else PendingReturn
+//! - Enemy: request damage + knockback along the flight direction; the hit spends one
+//!   pierce; with none left => PendingReturn
+//! - Player (enemy bullets): request damage + knockback; the bullet is absorbed
+//!   (PendingReturn) even if i-frames swallow the request
+//! - Destructible enemy bullet (player bullets): the enemy bullet is shot down
+//!   (PendingReturn); the player bullet spends one pierce, with none left => PendingReturn
 
 use avian2d::prelude::*;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
-use super::components::{Armour, Bullet, BulletState, CollisionEpoch, CollisionStamp, Health, PooledBullet};
+use crate::plugins::combat::DamageRequest;
+use crate::plugins::score::RunStats;
+
+use super::components::{Bullet, BulletState, CollisionEpoch, CollisionStamp, PooledBullet};
 use super::layers::Layer;
+use super::messages::BulletKind;
+
+/// Outward normal of the face of `aabb` that a circle of `radius` entered while moving
+/// from `prev` to `cur`, plus the fraction of that move at which it touched.
+///
+/// Slab test against the AABB grown by `radius`: the axis with the *latest* entry time
+/// is the face that was actually crossed. Works for the axis-aligned arena geometry;
+/// for anything else it yields the closest box face, which is a fine ricochet normal.
+fn entered_face(aabb: &ColliderAabb, radius: f32, prev: Vec2, cur: Vec2) -> (Vec2, f32) {
+    let min = aabb.min - Vec2::splat(radius);
+    let max = aabb.max + Vec2::splat(radius);
+    let d = cur - prev;
+
+    let entry = |p: f32, d: f32, lo: f32, hi: f32| -> f32 {
+        if d > 0.0 {
+            (lo - p) / d
+        } else if d < 0.0 {
+            (hi - p) / d
+        } else {
+            f32::NEG_INFINITY
+        }
+    };
+    let tx = entry(prev.x, d.x, min.x, max.x);
+    let ty = entry(prev.y, d.y, min.y, max.y);
+
+    if tx >= ty {
+        (Vec2::new(-d.x.signum(), 0.0), tx.clamp(0.0, 1.0))
+    } else {
+        (Vec2::new(0.0, -d.y.signum()), ty.clamp(0.0, 1.0))
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 struct CollisionTarget {
@@ -40,19 +82,56 @@ fn is_in_layer(layers: &CollisionLayers, layer: Layer) -> bool {
     layers.memberships.has_all(layer)
 }
 
+/// Damage request for `bullet` (moving at `vel`) hitting `target` at `pos`.
+#[inline]
+fn hit_request(bullet: &Bullet, target: Entity, pos: Vec2, vel: Vec2) -> DamageRequest {
+    DamageRequest {
+        source: bullet.owner,
+        target,
+        amount: bullet.damage,
+        damage_type: bullet.damage_type,
+        position: pos,
+        impulse: vel.normalize_or_zero() * bullet.knockback,
+        armour_piercing: bullet.armour_piercing,
+        status: bullet.status,
+        periodic: false,
+    }
+}
+
+/// One resolve run's input: this step's contacts, its dt, and the dedupe epoch.
+#[derive(SystemParam)]
+pub struct ResolveStep<'w, 's> {
+    time: Res<'w, Time<Fixed>>,
+    started: MessageReader<'w, 's, CollisionStart>,
+    epoch: ResMut<'w, CollisionEpoch>,
+}
+
+/// Where player hits go: damage requests, and the run's accuracy count.
+#[derive(SystemParam)]
+pub struct HitOutput<'w> {
+    damage: MessageWriter<'w, DamageRequest>,
+    stats: ResMut<'w, RunStats>,
+}
+
 pub fn process_player_bullet_collisions(
-    mut started: MessageReader<CollisionStart>,
-    mut epoch: ResMut<CollisionEpoch>,
+    mut step: ResolveStep,
     q_is_bullet: Query<(), With<PooledBullet>>,
-    mut q_bullet: Query<(&mut Bullet, &mut BulletState, &mut CollisionStamp), With<PooledBullet>>,
+    mut q_bullet: Query<(
+        &mut Bullet,
+        &mut BulletState,
+        &mut CollisionStamp,
+        &mut Position,
+        &mut LinearVelocity,
+    ), With<PooledBullet>>,
     q_layers: Query<&CollisionLayers>,
-    mut q_armour: Query<&mut Armour>,
-    mut q_health: Query<&mut Health>,
+    q_aabb: Query<&ColliderAabb>,
+    mut out: HitOutput,
 ) {
-    epoch.0 = epoch.0.wrapping_add(1);
-    let cur_epoch = epoch.0;
+    step.epoch.0 = step.epoch.0.wrapping_add(1);
+    let cur_epoch = step.epoch.0;
+    let dt = step.time.delta_secs();
 
-    for ev in started.read() {
+    for ev in step.started.read() {
         let (t1, t2) = targets(ev);
 
         let b1 = q_is_bullet.contains(t1.collider);
@@ -60,42 +139,129 @@ pub fn process_player_bullet_collisions(
         if !(b1 ^ b2) { continue; }
         let (bullet_side, other_side) = if b1 { (t1, t2) } else { (t2, t1) };
 
-        let (mut bullet, mut state, mut stamp) =
+        let (mut bullet, mut state, mut stamp, mut pos, mut vel) =
             q_bullet.get_mut(bullet_side.collider)
                 .expect("Bullet collider missing required pooled bullet components");
 
-        // Dedupe per bullet per resolve run
-        if stamp.last_epoch == cur_epoch { continue; }
-        stamp.last_epoch = cur_epoch;
-
         let other_layers = q_layers.get(other_side.collider)
             .expect("Collider missing CollisionLayers");
 
         if *state != BulletState::Active { continue; }
 
         if is_in_layer(other_layers, Layer::World) {
-            bullet.wall_bounces_left = bullet.wall_bounces_left.saturating_sub(1);
+            // Dedupe per bullet per resolve run (e.g. touching two walls in a corner).
+            if stamp.last_epoch == cur_epoch { continue; }
+            stamp.last_epoch = cur_epoch;
+
             if bullet.wall_bounces_left == 0 {
                 *state = BulletState::PendingReturn;
+                continue;
             }
+            bullet.wall_bounces_left -= 1;
+
+            let aabb = q_aabb.get(other_side.collider).expect("World collider missing ColliderAabb");
+            let prev = pos.0 - vel.0 * dt;
+            let (normal, t) = entered_face(aabb, bullet.radius(), prev, pos.0);
+
+            // Put the bullet back at the wall face, then reflect.
+            pos.0 = prev + (pos.0 - prev) * t;
+            vel.0 = (vel.0 - 2.0 * vel.0.dot(normal) * normal) * bullet.ricochet_retain;
             continue;
         }
 
         if is_in_layer(other_layers, Layer::Enemy) {
             let enemy_entity = other_side.gameplay_owner();
 
-            if let Ok(mut armour) = q_armour.get_mut(enemy_entity) {
-                if armour.hits_remaining > 0 {
-                    armour.hits_remaining = armour.hits_remaining.saturating_sub(1);
-                    continue;
-                }
+            // Dedupe per bullet per target: a piercing bullet hits each of the last
+            // `HitTargets::CAP` enemies it passed through only once.
+            if stamp.hit.contains(enemy_entity) { continue; }
+            // First enemy since activation: the shot connected (accuracy).
+            if stamp.hit.is_empty() {
+                out.stats.hits += 1;
             }
+            stamp.hit.insert(enemy_entity);
 
-            if let Ok(mut hp) = q_health.get_mut(enemy_entity) {
-                hp.hp -= bullet.damage;
+            out.damage.write(hit_request(&bullet, enemy_entity, pos.0, vel.0));
+
+            if bullet.pierce_left > 0 {
+                bullet.pierce_left -= 1;
+            } else {
+                *state = BulletState::PendingReturn;
             }
+        }
+    }
+}
+
+/// Resolve enemy bullets hitting the player.
+///
+/// Runs as its own resolve pass (own epoch) after `process_player_bullet_collisions`,
+/// so wall bounces are still handled there and a bullet that already went
+/// `PendingReturn` this tick is skipped here. I-frames are enforced by `apply_damage`.
+pub fn process_enemy_bullet_collisions(
+    mut started: MessageReader<CollisionStart>,
+    mut epoch: ResMut<CollisionEpoch>,
+    q_is_bullet: Query<(), With<PooledBullet>>,
+    mut q_bullet: Query<(&Bullet, &mut BulletState, &mut CollisionStamp, &Position, &LinearVelocity), With<PooledBullet>>,
+    q_layers: Query<&CollisionLayers>,
+    mut damage: MessageWriter<DamageRequest>,
+) {
+    epoch.0 = epoch.0.wrapping_add(1);
+    let cur_epoch = epoch.0;
+
+    for ev in started.read() {
+        let (t1, t2) = targets(ev);
+
+        let b1 = q_is_bullet.contains(t1.collider);
+        let b2 = q_is_bullet.contains(t2.collider);
+        if !(b1 ^ b2) { continue; }
+        let (bullet_side, other_side) = if b1 { (t1, t2) } else { (t2, t1) };
+
+        let other_layers = q_layers.get(other_side.collider)
+            .expect("Collider missing CollisionLayers");
+        if !is_in_layer(other_layers, Layer::Player) { continue; }
+
+        let (bullet, mut state, mut stamp, pos, vel) =
+            q_bullet.get_mut(bullet_side.collider)
+                .expect("Bullet collider missing required pooled bullet components");
+
+        // Dedupe per bullet per resolve run
+        if stamp.last_epoch == cur_epoch { continue; }
+        stamp.last_epoch = cur_epoch;
+
+        if *state != BulletState::Active { continue; }
+
+        damage.write(hit_request(bullet, other_side.gameplay_owner(), pos.0, vel.0));
+        *state = BulletState::PendingReturn;
+    }
+}
+
+/// Resolve player bullets shooting down destructible enemy bullets.
+///
+/// Only opted-in enemy bullets ever produce these contacts (see `pool::active_layers`);
+/// the other passes skip bullet/bullet pairs. Both sides must still be `Active`, so a
+/// non-piercing shot takes down at most one orb per tick. A shot-down explosive orb
+/// still detonates, like any other returned explosive.
+pub fn process_bullet_vs_bullet_collisions(
+    mut started: MessageReader<CollisionStart>,
+    mut q_bullet: Query<(&mut Bullet, &mut BulletState), With<PooledBullet>>,
+) {
+    for ev in started.read() {
+        let Ok([a, b]) = q_bullet.get_many_mut([ev.collider1, ev.collider2]) else { continue; };
+
+        let ((mut shot, mut shot_state), (orb, mut orb_state)) = match (a.0.kind, b.0.kind) {
+            (BulletKind::Player, BulletKind::Enemy) => (a, b),
+            (BulletKind::Enemy, BulletKind::Player) => (b, a),
+            _ => continue,
+        };
+
+        if !orb.destructible { continue; }
+        if *shot_state != BulletState::Active || *orb_state != BulletState::Active { continue; }
 
-            *state = BulletState::PendingReturn;
+        *orb_state = BulletState::PendingReturn;
+        if shot.pierce_left > 0 {
+            shot.pierce_left -= 1;
+        } else {
+            *shot_state = BulletState::PendingReturn;
         }
     }
 }
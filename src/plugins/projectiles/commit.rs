@@ -8,24 +8,34 @@
 //! - collide with nothing (filters empty)
 //!
 //! Centralizing these writes here prevents inconsistencies.
+//! Each bullet goes back to the pool of its `Bullet::kind`.
 
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
-use super::components::{BulletEntity, BulletState, PooledBullet};
-use super::pool::{inactive_bullet_layers, BulletPool};
+use super::components::{Bullet, BulletEntity, BulletState, PooledBullet};
+use super::pool::{inactive_bullet_layers, BulletPools};
 
-pub fn return_to_pool_commit(
-    mut pool: ResMut<BulletPool>,
-    mut q: Query<(
+/// Pooled bullets and the components a return resets.
+type Returning<'w, 's> = Query<
+    'w,
+    's,
+    (
         Entity,
-        &mut BulletState,
-        &mut Visibility,
-        &mut LinearVelocity,
-        &mut CollisionLayers,
-    ), With<PooledBullet>>,
+        &'static Bullet,
+        &'static mut BulletState,
+        &'static mut Visibility,
+        &'static mut LinearVelocity,
+        &'static mut CollisionLayers,
+    ),
+    With<PooledBullet>,
+>;
+
+pub fn return_to_pool_commit(
+    mut pools: ResMut<BulletPools>,
+    mut q: Returning,
 ) {
-    for (e, mut state, mut vis, mut vel, mut layers) in &mut q {
+    for (e, bullet, mut state, mut vis, mut vel, mut layers) in &mut q {
         if *state != BulletState::PendingReturn { continue; }
 
         *state = BulletState::Inactive;
@@ -33,6 +43,6 @@ pub fn return_to_pool_commit(
         vel.0 = Vec2::ZERO;
         *layers = inactive_bullet_layers();
 
-        pool.push_free(BulletEntity(e));
+        pools.get_mut(bullet.kind).push_free(BulletEntity(e));
     }
 }
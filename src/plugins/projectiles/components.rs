@@ -17,6 +17,12 @@
 
 use bevy::prelude::*;
 
+use crate::plugins::combat::status::StatusApply;
+use crate::plugins::combat::DamageType;
+
+use super::graze::GrazeMark;
+use super::messages::{BulletKind, Explosion, Explosive, Homing, SpawnBulletRequest};
+
 #[derive(Component)]
 pub struct Player;
 
@@ -50,19 +56,132 @@ impl Default for BulletState {
 }
 
 /// Bullet gameplay state.
+///
+/// `kind` is fixed when the pooled entity is spawned: each `BulletKind` has its own pool,
+/// so a bullet always returns to the pool it came from.
 #[derive(Component, Debug, Clone)]
 pub struct Bullet {
+    pub kind: BulletKind,
+    /// Monotonic activation stamp; lower = fired earlier (used by `RecycleOldest`).
+    pub activation: u32,
+    /// Who fired it (damage `source`); `None` for ownerless producers.
+    pub owner: Option<Entity>,
     pub damage: i32,
+    pub damage_type: DamageType,
     pub wall_bounces_left: u8,
+    /// Speed fraction kept per wall reflection.
+    pub ricochet_retain: f32,
+    /// Enemies this bullet may still pass through.
+    pub pierce_left: u8,
+    pub armour_piercing: bool,
+    /// Steering parameters; `None` flies straight.
+    pub homing: Option<Homing>,
+    /// Impulse (px/s at weight 1) applied along the flight direction on hit.
+    pub knockback: f32,
+    /// Blast payload; `None` is a plain bullet.
+    pub explosive: Option<Explosive>,
+    /// Status effect applied on hit; `None` applies nothing.
+    pub status: Option<StatusApply>,
+    /// Seconds of virtual time left before expiry.
+    pub life_remaining: f32,
+    /// Pixels of travel left before expiry.
+    pub range_remaining: f32,
+    /// Size of this activation (applied through `Transform::scale`).
+    pub scale: f32,
+    /// Can be shot down by player bullets (enemy bullets only).
+    pub destructible: bool,
 }
 
 impl Bullet {
-    pub const DEFAULT_WALL_BOUNCES: u8 = 3;
+    /// Wall reflections by default; the third wall contact returns the bullet.
+    pub const DEFAULT_WALL_BOUNCES: u8 = 2;
+    /// Radius of the pooled bullet collider (pixels).
+    pub const RADIUS: f32 = 4.0;
+    pub const DEFAULT_LIFETIME_SECS: f32 = 4.0;
+    pub const DEFAULT_MAX_RANGE: f32 = 4000.0;
+
+    /// Inactive pooled bullet of the given kind.
+    pub fn pooled(kind: BulletKind) -> Self {
+        Self {
+            kind,
+            activation: 0,
+            owner: None,
+            damage: 1,
+            damage_type: DamageType::Kinetic,
+            wall_bounces_left: Self::DEFAULT_WALL_BOUNCES,
+            ricochet_retain: 1.0,
+            pierce_left: 0,
+            armour_piercing: false,
+            homing: None,
+            explosive: None,
+            status: None,
+            knockback: 0.0,
+            life_remaining: 0.0,
+            range_remaining: 0.0,
+            scale: 1.0,
+            destructible: false,
+        }
+    }
+
+    /// Overwrite per-shot state from a spawn request. `kind` is left untouched.
+    #[inline]
+    pub fn reset_for_fire(&mut self, req: &SpawnBulletRequest, activation: u32) {
+        self.activation = activation;
+        self.owner = req.owner;
+        self.damage = req.damage;
+        self.damage_type = req.modifiers.damage_type;
+        self.wall_bounces_left = req.modifiers.ricochet.bounces;
+        self.ricochet_retain = req.modifiers.ricochet.speed_retain;
+        self.pierce_left = req.modifiers.pierce;
+        self.armour_piercing = req.modifiers.armour_piercing;
+        self.homing = req.modifiers.homing;
+        self.explosive = req.modifiers.explosive;
+        self.status = req.modifiers.status;
+        self.knockback = req.modifiers.knockback;
+        self.life_remaining = req.lifetime;
+        self.range_remaining = req.max_range;
+        self.scale = req.scale;
+        self.destructible = req.modifiers.destructible;
+    }
+
+    /// Collider radius of this activation, in pixels.
+    #[inline]
+    pub fn radius(&self) -> f32 {
+        Self::RADIUS * self.scale
+    }
 
     #[inline]
-    pub fn reset_for_fire(&mut self, damage: i32) {
-        self.damage = damage;
-        self.wall_bounces_left = Self::DEFAULT_WALL_BOUNCES;
+    pub fn is_expired(&self) -> bool {
+        self.life_remaining <= 0.0 || self.range_remaining <= 0.0
+    }
+
+    /// The blast this bullet produces when returned at `pos`, if it is explosive.
+    ///
+    /// An expired bullet only detonates when its payload opts into `on_expiry`.
+    pub fn detonation(&self, pos: Vec2) -> Option<Explosion> {
+        let ex = self.explosive?;
+        if self.is_expired() && !ex.on_expiry {
+            return None;
+        }
+        Some(Explosion {
+            kind: self.kind,
+            owner: self.owner,
+            pos,
+            radius: ex.radius,
+            damage: ex.damage,
+            damage_type: ex.damage_type,
+            edge_falloff: ex.edge_falloff,
+            impulse: ex.impulse,
+            armour_piercing: self.armour_piercing,
+        })
+    }
+
+    /// Consume lifetime/range for one step. Returns true once either budget runs out.
+    #[inline]
+    pub fn age(&mut self, dt: f32, distance: f32) -> bool {
+        self.life_remaining -= dt;
+        self.range_remaining -= distance;
+        self.is_expired()
     }
 }
 
@@ -80,7 +199,12 @@ impl Armour {
 
     #[inline]
     pub fn wear_one(&mut self) {
-        self.hits_remaining = self.hits_remaining.saturating_sub(1);
+        self.wear(1);
+    }
+
+    #[inline]
+    pub fn wear(&mut self, hits: u16) {
+        self.hits_remaining = self.hits_remaining.saturating_sub(hits);
     }
 }
 
@@ -89,6 +213,99 @@ pub struct Health {
     pub hp: i32,
 }
 
+/// Damage-immunity window ("i-frames") granted after the player takes a hit.
+///
+/// Always present on the player: a hit only writes a number, so there is no
+/// structural churn. `remaining` is in seconds of virtual time, which means
+/// hitstop/slowmo pause the window too.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Invulnerability {
+    pub remaining: f32,
+}
+
+impl Invulnerability {
+    pub const DEFAULT_WINDOW_SECS: f32 = 0.75;
+
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    #[inline]
+    pub fn grant(&mut self, secs: f32) {
+        self.remaining = self.remaining.max(secs);
+    }
+
+    #[inline]
+    pub fn tick(&mut self, dt: f32) {
+        self.remaining = (self.remaining - dt).max(0.0);
+    }
+}
+
+/// Mass-like scale for hit impulses: knockback velocity = impulse / weight.
+///
+/// Separate from Avian's mass so designers can make an enemy "heavy" to hits without
+/// changing how it behaves in contacts.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Weight(pub f32);
+
+impl Default for Weight {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Knockback velocity plus a short stun window.
+///
+/// Always present on anything that can be shoved (no structural churn per hit).
+/// Movement systems don't overwrite velocity while stunned: they `blend` their desired
+/// velocity with the knockback, handing control back as the window closes.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Knockback {
+    pub velocity: Vec2,
+    /// Seconds of virtual time left in the stun window.
+    pub stun_remaining: f32,
+}
+
+impl Knockback {
+    pub const STUN_SECS: f32 = 0.18;
+    /// Exponential decay rate of the knockback velocity, per second.
+    pub const DAMPING: f32 = 10.0;
+    /// Below this speed (px/s) the residual knockback snaps to zero.
+    const REST_SPEED: f32 = 1.0;
+
+    /// Add an impulse (px/s at weight 1) and (re)open the stun window.
+    #[inline]
+    pub fn apply(&mut self, impulse: Vec2, weight: Weight) {
+        if impulse == Vec2::ZERO { return; }
+        self.velocity += impulse / weight.0.max(0.01);
+        self.stun_remaining = self.stun_remaining.max(Self::STUN_SECS);
+    }
+
+    #[inline]
+    pub fn is_stunned(&self) -> bool {
+        self.stun_remaining > 0.0
+    }
+
+    /// Combine a movement system's desired velocity with the knockback.
+    ///
+    /// Control ramps from 0 (just hit) back to 1 (window over).
+    #[inline]
+    pub fn blend(&self, desired: Vec2) -> Vec2 {
+        let control = 1.0 - (self.stun_remaining / Self::STUN_SECS).clamp(0.0, 1.0);
+        desired * control + self.velocity
+    }
+
+    #[inline]
+    pub fn tick(&mut self, dt: f32) {
+        self.velocity *= (-Self::DAMPING * dt).exp();
+        if self.velocity.length_squared() < Self::REST_SPEED * Self::REST_SPEED {
+            self.velocity = Vec2::ZERO;
+        }
+        self.stun_remaining = (self.stun_remaining - dt).max(0.0);
+    }
+}
+
 /// Newtype for pooled bullet entities.
 ///
 /// This encodes an important invariant:
@@ -103,9 +320,46 @@ pub struct BulletEntity(pub Entity);
 ///
 /// **Idea:** store "already processed this tick" on the bullet itself.
 /// This is a data-driven alternative to allocating/clearing a HashSet every tick.
+///
+/// `hit` applies the same idea to piercing: a bullet never damages an enemy it already
+/// passed through this flight (up to `HitTargets::CAP` of them). `graze` records the
+/// bullet's near miss with the player (see `graze`). Both reset on every activation.
 #[derive(Component, Debug, Default, Clone, Copy)]
 pub struct CollisionStamp {
     pub last_epoch: u32,
+    pub hit: HitTargets,
+    pub graze: GrazeMark,
+}
+
+/// Enemies a bullet has hit since activation (inline, no allocation).
+///
+/// Holds the last `CAP` hits; only a bullet piercing more enemies than that could
+/// hit a forgotten one again.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct HitTargets {
+    hits: [Option<Entity>; Self::CAP],
+    next: usize,
+}
+
+impl HitTargets {
+    pub const CAP: usize = 8;
+
+    #[inline]
+    pub fn contains(&self, e: Entity) -> bool {
+        self.hits.contains(&Some(e))
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.hits[0].is_none()
+    }
+
+    /// Record `e`, overwriting the oldest hit once full.
+    #[inline]
+    pub fn insert(&mut self, e: Entity) {
+        self.hits[self.next] = Some(e);
+        self.next = (self.next + 1) % Self::CAP;
+    }
 }
 
 /// Global epoch incremented once per collision-resolve run.
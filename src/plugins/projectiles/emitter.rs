@@ -0,0 +1,262 @@
+//! Declarative bullet-pattern emitters (the enemy side of the spawn pipeline).
+//!
+//! # Data flow
+//! ```text
+//!   assets/patterns/*.pattern.ron ──(RonAssetLoader)──> Assets<BulletPattern>
+//!   FixedUpdate: step_emitters
+//!       Emitter (handle + runtime phase) ──(BulletPattern::volley)──> SpawnBulletRequest × N
+//!   Update: allocate_bullets_from_pool (same consumer as player fire)
+//!       caught_up: moved along vel for the time since the volley was due
+//! ```
+//!
+//! Patterns are shared by many enemies, so unlike `Weapon` the definition is not copied
+//! per entity: each emitter looks its pattern up once per step (not once per bullet).
+//! An emitter whose pattern has not loaded yet simply waits.
+//!
+//! # Determinism
+//! Emitters step on `Time<Fixed>` with no RNG. Given the same start phase and target
+//! positions, a pattern produces the same bullets on every run. Each volley carries the
+//! fixed time it was due (`fired_at`), so neither the step size nor the frame rate moves
+//! where its bullets are.
+//!
+//! # Shapes
+//! - `Ring`: bullets evenly spaced around the full circle.
+//! - `Spiral`: a ring with few arms that advances `step_deg` after every volley.
+//! - `Fan`: bullets across `spread_deg`, centred on the base direction.
+//! - `AimedBurst`: `shots` fans aimed at the player `shot_interval_secs` apart, then `period_secs`.
+//! - `Wave`: a fan whose centre sways `± amplitude_deg` at `frequency_hz`.
+//!
+//! Every shape also rotates its base direction by `spin_deg_per_sec` (angular velocity),
+//! and `aimed: true` measures that base direction from the emitter toward the player.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::common::ron_asset::RonAsset;
+
+use super::components::{Bullet, Player, PlayerEntity};
+use super::messages::{BulletKind, BulletModifiers, Ricochet, SpawnBulletRequest};
+
+/// Shortest allowed gap between volleys; keeps a bad file from spinning the step loop.
+pub const MIN_INTERVAL_SECS: f32 = 0.01;
+
+/// Volley layout.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum PatternShape {
+    Ring { count: u16 },
+    Spiral { arms: u16, step_deg: f32 },
+    Fan { count: u16, spread_deg: f32 },
+    AimedBurst { shots: u16, pellets: u16, spread_deg: f32, shot_interval_secs: f32 },
+    Wave { count: u16, spread_deg: f32, amplitude_deg: f32, frequency_hz: f32 },
+}
+
+/// Designer-facing pattern description.
+///
+/// Missing fields fall back to `Default` (a slow 12-bullet ring once per second).
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct BulletPattern {
+    pub shape: PatternShape,
+    /// Seconds between volleys (between bursts for `AimedBurst`).
+    pub period_secs: f32,
+    /// Angular velocity of the base direction, in degrees per second.
+    pub spin_deg_per_sec: f32,
+    /// Measure the base direction from the emitter toward the player.
+    pub aimed: bool,
+    /// Bullet speed in pixels per second.
+    pub speed: f32,
+    pub damage: i32,
+    pub modifiers: BulletModifiers,
+    /// Distance from the emitter's centre to the bullet spawn point, in pixels.
+    pub muzzle_offset: f32,
+    pub lifetime_secs: f32,
+    pub max_range: f32,
+    /// Bullet size multiplier (large, slow orbs read as shootable).
+    pub bullet_scale: f32,
+}
+
+impl Default for BulletPattern {
+    fn default() -> Self {
+        Self {
+            shape: PatternShape::Ring { count: 12 },
+            period_secs: 1.0,
+            spin_deg_per_sec: 0.0,
+            aimed: false,
+            speed: 260.0,
+            damage: 1,
+            // Enemy fire dies on walls by default; bouncing bullet-hell gets unreadable.
+            modifiers: BulletModifiers {
+                ricochet: Ricochet { bounces: 0, ..default() },
+                ..default()
+            },
+            muzzle_offset: 20.0,
+            lifetime_secs: Bullet::DEFAULT_LIFETIME_SECS,
+            max_range: Bullet::DEFAULT_MAX_RANGE,
+            bullet_scale: 1.0,
+        }
+    }
+}
+
+impl RonAsset for BulletPattern {
+    const EXTENSIONS: &'static [&'static str] = &["pattern.ron"];
+}
+
+/// `count` angles spread evenly across `spread` radians, centred on `center`.
+fn fan(center: f32, count: u16, spread: f32) -> impl Iterator<Item = f32> {
+    let count = count.max(1);
+    let step = if count > 1 { spread / (count - 1) as f32 } else { 0.0 };
+    let first = if count > 1 { center - spread * 0.5 } else { center };
+    (0..count).map(move |i| first + step * i as f32)
+}
+
+/// `count` angles spread evenly around the full circle, starting at `start`.
+fn ring(start: f32, count: u16) -> impl Iterator<Item = f32> {
+    let count = count.max(1);
+    (0..count).map(move |i| start + TAU * i as f32 / count as f32)
+}
+
+impl BulletPattern {
+    /// Bullet directions (unit vectors) for one volley.
+    ///
+    /// `base` is the emitter's base angle in radians (spin + spiral phase, plus the aim
+    /// angle when `aimed`); `elapsed` drives the `Wave` sway.
+    pub fn volley(&self, base: f32, elapsed: f32) -> Vec<Vec2> {
+        let angles: Vec<f32> = match self.shape {
+            PatternShape::Ring { count } => ring(base, count).collect(),
+            PatternShape::Spiral { arms, .. } => ring(base, arms).collect(),
+            PatternShape::Fan { count, spread_deg } => {
+                fan(base, count, spread_deg.to_radians()).collect()
+            }
+            PatternShape::AimedBurst { pellets, spread_deg, .. } => {
+                fan(base, pellets, spread_deg.to_radians()).collect()
+            }
+            PatternShape::Wave { count, spread_deg, amplitude_deg, frequency_hz } => {
+                let sway = amplitude_deg.to_radians() * (TAU * frequency_hz * elapsed).sin();
+                fan(base + sway, count, spread_deg.to_radians()).collect()
+            }
+        };
+        angles.into_iter().map(Vec2::from_angle).collect()
+    }
+
+    #[inline]
+    fn is_aimed(&self) -> bool {
+        self.aimed || matches!(self.shape, PatternShape::AimedBurst { .. })
+    }
+
+    fn request(&self, origin: Vec2, dir: Vec2, owner: Entity, fired_at: f32) -> SpawnBulletRequest {
+        SpawnBulletRequest {
+            kind: BulletKind::Enemy,
+            pos: origin + dir * self.muzzle_offset,
+            vel: dir * self.speed,
+            damage: self.damage,
+            modifiers: self.modifiers,
+            lifetime: self.lifetime_secs,
+            max_range: self.max_range,
+            owner: Some(owner),
+            scale: self.bullet_scale,
+            fired_at: Some(fired_at),
+        }
+    }
+}
+
+/// A pattern source on an entity, plus its runtime phase.
+///
+/// Always present on emitting enemies; dying enemies set `enabled = false` instead of
+/// removing the component (no archetype churn).
+#[derive(Component, Debug, Clone)]
+pub struct Emitter {
+    pub pattern: Handle<BulletPattern>,
+    pub enabled: bool,
+    /// Seconds of fixed time until the next volley.
+    pub cooldown: f32,
+    /// Base angle in radians (spin and spiral steps accumulate here).
+    pub angle: f32,
+    /// Seconds of fixed time since the emitter started (wave phase).
+    pub elapsed: f32,
+    /// Volleys already fired in the current `AimedBurst`.
+    pub burst_fired: u16,
+}
+
+impl Emitter {
+    pub fn new(pattern: Handle<BulletPattern>) -> Self {
+        Self { pattern, enabled: true, cooldown: 0.0, angle: 0.0, elapsed: 0.0, burst_fired: 0 }
+    }
+
+    /// Start with a delay (e.g. to stagger several emitters sharing one pattern).
+    pub fn with_delay(mut self, secs: f32) -> Self {
+        self.cooldown = secs;
+        self
+    }
+
+    /// Advance by `dt` and call `fire(base_angle, elapsed, late)` for every volley that is
+    /// due; `late` is how long before the end of the step it was due.
+    ///
+    /// Several volleys can be due in one step when the period is shorter than `dt`; they
+    /// fire in order with the phase each would have had.
+    pub fn step(&mut self, pattern: &BulletPattern, dt: f32, mut fire: impl FnMut(f32, f32, f32)) {
+        let spin = pattern.spin_deg_per_sec.to_radians();
+        self.elapsed += dt;
+        self.angle = (self.angle + spin * dt).rem_euclid(TAU);
+        self.cooldown -= dt;
+
+        while self.cooldown <= 0.0 {
+            let late = -self.cooldown;
+            fire((self.angle - spin * late).rem_euclid(TAU), self.elapsed - late, late);
+
+            self.cooldown += match pattern.shape {
+                PatternShape::Spiral { step_deg, .. } => {
+                    self.angle = (self.angle + step_deg.to_radians()).rem_euclid(TAU);
+                    pattern.period_secs
+                }
+                PatternShape::AimedBurst { shots, shot_interval_secs, .. } => {
+                    self.burst_fired += 1;
+                    if self.burst_fired < shots {
+                        shot_interval_secs
+                    } else {
+                        self.burst_fired = 0;
+                        pattern.period_secs
+                    }
+                }
+                _ => pattern.period_secs,
+            }
+            .max(MIN_INTERVAL_SECS);
+        }
+    }
+}
+
+pub fn step_emitters(
+    time: Res<Time<Fixed>>,
+    patterns: Res<Assets<BulletPattern>>,
+    player_e: Res<PlayerEntity>,
+    q_player: Query<&Transform, With<Player>>,
+    mut q_emitters: Query<(Entity, &Transform, &mut Emitter), Without<Player>>,
+    mut requests: MessageWriter<SpawnBulletRequest>,
+) {
+    let dt = time.delta_secs();
+    let now = time.elapsed_secs();
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let target = q_player.get(player).expect("PlayerEntity invalid").translation.truncate();
+
+    for (e, tf, mut emitter) in &mut q_emitters {
+        if !emitter.enabled { continue; }
+        let Some(pattern) = patterns.get(&emitter.pattern) else { continue; };
+
+        let origin = tf.translation.truncate();
+        let aim = if pattern.is_aimed() {
+            (target - origin).try_normalize().map_or(0.0, Vec2::to_angle)
+        } else {
+            0.0
+        };
+
+        emitter.step(pattern, dt, |base, elapsed, late| {
+            requests.write_batch(
+                pattern
+                    .volley(aim + base, elapsed)
+                    .into_iter()
+                    .map(|dir| pattern.request(origin, dir, e, now - late)),
+            );
+        });
+    }
+}
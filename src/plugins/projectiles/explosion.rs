@@ -0,0 +1,71 @@
+//! Explosive bullets: detonation + splash damage.
+//!
+//! ```text
+//!   resolve / expire ──> BulletState::PendingReturn
+//!   detonate_explosives ──(Bullet::detonation)──> Explosion message
+//!   apply_explosions ──(SpatialQuery::shape_intersections)──> DamageRequest per target
+//!   return_to_pool_commit (unchanged: still the only recycling path)
+//! ```
+//!
+//! Detonation keys off `PendingReturn` instead of being sprinkled through every
+//! resolve branch: whatever consumed the bullet (enemy, wall, player, expiry), an
+//! explosive payload goes off where the bullet stopped. Bullets that escaped the arena
+//! are the exception; nobody would see or feel that blast.
+//!
+//! # Damage rules
+//! One `DamageRequest` per target caught in the blast, with falloff damage and an impulse
+//! pushing away from the centre. Armour, i-frames and knockback scaling are then the
+//! same as for a direct hit, because `apply_damage` handles both.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::plugins::world::ArenaBounds;
+
+use crate::plugins::combat::DamageRequest;
+
+use super::components::{Bullet, BulletState, PooledBullet};
+use super::layers::Layer;
+use super::lifetime::ARENA_ESCAPE_MARGIN;
+use super::messages::{BulletKind, Explosion};
+
+pub fn detonate_explosives(
+    bounds: Res<ArenaBounds>,
+    q: Query<(&Bullet, &BulletState, &Position), With<PooledBullet>>,
+    mut explosions: MessageWriter<Explosion>,
+) {
+    for (bullet, state, pos) in &q {
+        if *state != BulletState::PendingReturn { continue; }
+        if !bounds.contains_with_margin(pos.0, ARENA_ESCAPE_MARGIN) { continue; }
+
+        if let Some(explosion) = bullet.detonation(pos.0) {
+            explosions.write(explosion);
+        }
+    }
+}
+
+/// Layer a blast from `kind` can hurt.
+#[inline]
+fn victims(kind: BulletKind) -> Layer {
+    match kind {
+        BulletKind::Player => Layer::Enemy,
+        BulletKind::Enemy => Layer::Player,
+    }
+}
+
+pub fn apply_explosions(
+    mut explosions: MessageReader<Explosion>,
+    spatial: SpatialQuery,
+    q_positions: Query<&Position, Without<PooledBullet>>,
+    mut damage: MessageWriter<DamageRequest>,
+) {
+    for ex in explosions.read() {
+        let filter = SpatialQueryFilter::from_mask(victims(ex.kind));
+        let hits = spatial.shape_intersections(&Collider::circle(ex.radius), ex.pos, 0.0, &filter);
+
+        for target in hits {
+            let Ok(pos) = q_positions.get(target) else { continue; };
+            damage.write(ex.damage_request(target, pos.0));
+        }
+    }
+}
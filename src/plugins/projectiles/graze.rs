@@ -0,0 +1,117 @@
+//! Graze: score enemy bullets that pass close to the player without hitting.
+//!
+//! ```text
+//!   GrazeZone (on the player, spawned by player::spawn)
+//!   detect_grazes (fixed step, after enemy-fire resolve)
+//!       SpatialQuery::shape_intersections(zone circle, EnemyBullet)
+//!       active enemy bullet inside the zone  ──> CollisionStamp.graze = Near
+//!       Near bullet back outside the zone    ──> Counted, GrazeCounter += 1, Grazed message
+//! ```
+//!
+//! A graze is awarded when the bullet *leaves* the zone, so a bullet that goes on to hit
+//! the player (and is returned by `process_enemy_bullet_collisions`) never counts.
+//! The rules live in `GrazeMark::step`, so they are testable without a physics world.
+//!
+//! # Dedupe
+//! Same idea as the collision epoch: the "already grazed" fact lives on the bullet's
+//! `CollisionStamp` instead of a HashSet. `Counted` sticks until the bullet is fired
+//! again, so a homing bullet circling the player counts once.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::components::{Bullet, BulletState, CollisionStamp, PlayerEntity, PooledBullet};
+use super::layers::Layer;
+use super::messages::Grazed;
+
+/// Points for one grazed bullet.
+pub const GRAZE_POINTS: u32 = 5;
+
+/// Progress of one bullet past the player's graze zone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GrazeMark {
+    #[default]
+    Clear,
+    /// Inside the zone; counts once it leaves without hitting.
+    Near,
+    Counted,
+}
+
+impl GrazeMark {
+    /// Advance one fixed step given whether the bullet is `inside` the zone and
+    /// `touching` the hurtbox. Returns true on the step that counts the graze.
+    pub fn step(&mut self, inside: bool, touching: bool) -> bool {
+        match *self {
+            GrazeMark::Clear if inside && !touching => {
+                *self = GrazeMark::Near;
+                false
+            }
+            GrazeMark::Near if !inside => {
+                *self = GrazeMark::Counted;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Near-miss zone around the player.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct GrazeZone {
+    /// Bullets whose collider overlaps this circle around the player graze.
+    pub radius: f32,
+    /// Player hurtbox radius; bullets overlapping it are hits, not grazes.
+    pub hurt_radius: f32,
+}
+
+/// Grazes in the current run (reset on entering `InGame`).
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct GrazeCounter {
+    pub count: u32,
+    pub points: u32,
+}
+
+pub fn reset_graze_counter(mut counter: ResMut<GrazeCounter>) {
+    *counter = GrazeCounter::default();
+}
+
+pub fn detect_grazes(
+    player_e: Res<PlayerEntity>,
+    q_player: Query<(&Position, &GrazeZone)>,
+    spatial: SpatialQuery,
+    mut q_bullet: Query<(&Bullet, &BulletState, &mut CollisionStamp, &Position), With<PooledBullet>>,
+    mut near: Local<Vec<Entity>>,
+    mut counter: ResMut<GrazeCounter>,
+    mut grazed: MessageWriter<Grazed>,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let (player_pos, zone) = q_player.get(player).expect("PlayerEntity missing GrazeZone");
+
+    // Pooled bullets park on the player layer while inactive, so this finds live enemy fire.
+    let filter = SpatialQueryFilter::from_mask(Layer::EnemyBullet);
+    let inside = spatial.shape_intersections(&Collider::circle(zone.radius), player_pos.0, 0.0, &filter);
+
+    for &e in &inside {
+        let Ok((bullet, state, mut stamp, pos)) = q_bullet.get_mut(e) else { continue; };
+        if *state != BulletState::Active { continue; }
+
+        let touch = zone.hurt_radius + bullet.radius();
+        let touching = pos.0.distance_squared(player_pos.0) <= touch * touch;
+        stamp.graze.step(true, touching);
+        if stamp.graze == GrazeMark::Near && !near.contains(&e) {
+            near.push(e);
+        }
+    }
+
+    near.retain(|&e| {
+        let Ok((_, state, mut stamp, pos)) = q_bullet.get_mut(e) else { return false; };
+        // Returned (e.g. it hit the player) or refired since: nothing left to count.
+        if *state != BulletState::Active || stamp.graze != GrazeMark::Near { return false; }
+        if !stamp.graze.step(inside.contains(&e), false) { return true; }
+
+        counter.count += 1;
+        counter.points += GRAZE_POINTS;
+        grazed.write(Grazed { pos: pos.0, points: GRAZE_POINTS });
+        false
+    });
+}
@@ -0,0 +1,221 @@
+//! Hitscan + beam weapons: instant rays instead of pooled bullets.
+//!
+//! ```text
+//!   request_player_bullets ──> HitscanRequest (one per pellet / beam tick)
+//!   resolve_hitscan (fixed step, after the physics step)
+//!       trace: SpatialQuery::cast_ray against World | Enemy, reflect off World
+//!       ──> DamageRequest per enemy hit (same armour rules as bullets)
+//!       ──> RayFx tracers (drawn with gizmos, fade on virtual time)
+//! ```
+//!
+//! # Why trace in the fixed step?
+//! Damage is applied in the fixed step. Requests are written in `Update`, but the
+//! `HitscanRequest` buffer is advanced on the fixed clock, so a frame without a fixed
+//! step (high frame rate, hitstop) delays a shot instead of dropping it.
+//!
+//! # Trace rules
+//! - A wall reflects the ray while bounces remain, otherwise the ray stops there.
+//! - An enemy takes a hit; the ray continues through it while pierce remains.
+//! - Every enemy is hit at most once per ray, even if a bounce brings the ray back.
+//! - Dying enemies (filters cleared) are transparent, like they are to bullets.
+//! - `range` is the total length over all bounces.
+//!
+//! `trace` takes the ray cast as a closure, so the rules are testable without a
+//! physics world.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::plugins::combat::DamageRequest;
+use crate::plugins::score::RunStats;
+
+use super::layers::Layer;
+use super::messages::HitscanRequest;
+
+/// A reflected ray restarts this far off the wall so it doesn't hit the same face again.
+const BOUNCE_SKIN: f32 = 0.5;
+/// How long a hitscan tracer stays on screen (seconds of virtual time).
+pub const TRACER_SECS: f32 = 0.08;
+/// Beam segments are redrawn every tick; this bridges ticks at 10+ shots per second.
+pub const BEAM_TRACER_SECS: f32 = 0.1;
+
+/// First thing a ray touched.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayContact {
+    pub entity: Entity,
+    pub distance: f32,
+    /// Surface normal at the contact (used for wall reflection).
+    pub normal: Vec2,
+    /// `true` for `Layer::World` geometry, `false` for a damageable target.
+    pub world: bool,
+}
+
+/// A damageable target crossed by a ray.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    pub point: Vec2,
+    /// Travel direction of the ray segment that hit.
+    pub dir: Vec2,
+}
+
+/// Result of tracing one ray.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RayTrace {
+    /// Straight pieces of the ray, split at each bounce.
+    pub segments: Vec<(Vec2, Vec2)>,
+    pub hits: Vec<RayHit>,
+}
+
+/// Trace one ray from `origin` along the normalized `dir`.
+///
+/// `cast(from, dir, max_distance, already_hit)` returns the closest contact, skipping
+/// every entity in `already_hit`.
+pub fn trace(
+    origin: Vec2,
+    dir: Vec2,
+    range: f32,
+    bounces: u8,
+    pierce: u8,
+    mut cast: impl FnMut(Vec2, Vec2, f32, &[Entity]) -> Option<RayContact>,
+) -> RayTrace {
+    let mut out = RayTrace::default();
+    let mut already_hit = Vec::new();
+
+    let mut from = origin;
+    let mut dir = dir;
+    let mut seg_start = origin;
+    let mut remaining = range;
+    let mut bounces_left = bounces;
+    let mut pierce_left = pierce;
+
+    loop {
+        let Some(contact) = cast(from, dir, remaining, &already_hit) else {
+            out.segments.push((seg_start, from + dir * remaining));
+            break;
+        };
+
+        let point = from + dir * contact.distance;
+        remaining -= contact.distance;
+
+        if contact.world {
+            out.segments.push((seg_start, point));
+            if bounces_left == 0 { break; }
+            bounces_left -= 1;
+
+            dir = dir - 2.0 * dir.dot(contact.normal) * contact.normal;
+            from = point + contact.normal * BOUNCE_SKIN;
+            seg_start = point;
+            continue;
+        }
+
+        out.hits.push(RayHit { entity: contact.entity, point, dir });
+        already_hit.push(contact.entity);
+        from = point;
+
+        if pierce_left == 0 {
+            out.segments.push((seg_start, point));
+            break;
+        }
+        pierce_left -= 1;
+    }
+
+    out
+}
+
+/// Closest World or live Enemy contact along a ray.
+fn cast_ray(
+    spatial: &SpatialQuery,
+    q_layers: &Query<&CollisionLayers>,
+    from: Vec2,
+    dir: Vec2,
+    max_distance: f32,
+    already_hit: &[Entity],
+) -> Option<RayContact> {
+    let dir = Dir2::new(dir).ok()?;
+    let mut filter = SpatialQueryFilter::from_mask([Layer::World, Layer::Enemy])
+        .with_excluded_entities(already_hit.iter().copied());
+
+    loop {
+        let hit = spatial.cast_ray(from, dir, max_distance, true, &filter)?;
+        let layers = q_layers.get(hit.entity).expect("ray hit a collider without CollisionLayers");
+
+        let world = layers.memberships.has_all(Layer::World);
+        if world || layers.filters.has_all(Layer::PlayerBullet) {
+            return Some(RayContact { entity: hit.entity, distance: hit.distance, normal: hit.normal, world });
+        }
+
+        // Dying enemy: see through it and cast again.
+        filter.excluded_entities.insert(hit.entity);
+    }
+}
+
+/// A fading ray segment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tracer {
+    pub from: Vec2,
+    pub to: Vec2,
+    /// Seconds of virtual time left on screen.
+    pub remaining: f32,
+    pub beam: bool,
+}
+
+/// Ray presentation: segments traced recently.
+#[derive(Resource, Debug, Default)]
+pub struct RayFx {
+    pub tracers: Vec<Tracer>,
+}
+
+pub fn resolve_hitscan(
+    mut shots: MessageReader<HitscanRequest>,
+    spatial: SpatialQuery,
+    q_layers: Query<&CollisionLayers>,
+    mut fx: ResMut<RayFx>,
+    mut damage: MessageWriter<DamageRequest>,
+    mut stats: ResMut<RunStats>,
+) {
+    for shot in shots.read() {
+        let traced = trace(
+            shot.origin,
+            shot.dir,
+            shot.range,
+            shot.bounces,
+            shot.modifiers.pierce,
+            |from, dir, max, already_hit| cast_ray(&spatial, &q_layers, from, dir, max, already_hit),
+        );
+
+        if !traced.hits.is_empty() {
+            stats.hits += 1;
+        }
+        damage.write_batch(traced.hits.iter().map(|hit| shot.damage_request(hit.entity, hit.point, hit.dir)));
+
+        let remaining = if shot.beam { BEAM_TRACER_SECS } else { TRACER_SECS };
+        fx.tracers.extend(
+            traced
+                .segments
+                .iter()
+                .map(|&(from, to)| Tracer { from, to, remaining, beam: shot.beam }),
+        );
+    }
+}
+
+/// Fade tracers on virtual time (hitstop freezes them with everything else).
+pub fn fade_ray_fx(time: Res<Time<Virtual>>, mut fx: ResMut<RayFx>) {
+    let dt = time.delta_secs();
+    fx.tracers.retain_mut(|t| {
+        t.remaining -= dt;
+        t.remaining > 0.0
+    });
+}
+
+pub fn draw_ray_fx(fx: Res<RayFx>, mut gizmos: Gizmos) {
+    for t in &fx.tracers {
+        let (color, max) = if t.beam {
+            (Color::srgb(0.4, 0.9, 1.0), BEAM_TRACER_SECS)
+        } else {
+            (Color::srgb(1.0, 0.95, 0.6), TRACER_SECS)
+        };
+        let alpha = (t.remaining / max).clamp(0.0, 1.0);
+        gizmos.line_2d(t.from, t.to, color.with_alpha(alpha));
+    }
+}
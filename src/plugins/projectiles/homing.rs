@@ -0,0 +1,88 @@
+//! Homing: steer active bullets toward the nearest valid target.
+//!
+//! Runs in the fixed step *before* the physics step, like `apply_movement`, so the
+//! solver integrates the steered velocity in the same tick.
+//!
+//! ```text
+//!   Player bullets ──> nearest Enemy that still accepts PlayerBullet hits
+//!   Enemy bullets  ──> the player (PlayerEntity)
+//! ```
+//!
+//! Homing is data on `Bullet` (copied from the request), not a marker component:
+//! non-homing bullets cost one `Option` check and the pool never changes archetype.
+//!
+//! # Cost
+//! Player bullets scan every enemy (bullets × enemies). That is fine for the enemy counts
+//! we have; a spatial index can replace the scan without touching the steering math.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use super::components::{Bullet, BulletState, Enemy, PlayerEntity, PooledBullet};
+use super::layers::Layer;
+use super::messages::{BulletKind, Homing};
+
+/// Pick the closest candidate inside the homing range and acquisition cone.
+///
+/// `heading` must be normalized. Returns the offset from `pos` to the chosen target.
+pub fn acquire(
+    homing: &Homing,
+    pos: Vec2,
+    heading: Vec2,
+    candidates: impl IntoIterator<Item = Vec2>,
+) -> Option<Vec2> {
+    let min_cos = (homing.cone_deg.to_radians() * 0.5).cos();
+    let range_sq = homing.range * homing.range;
+
+    candidates
+        .into_iter()
+        .map(|target| target - pos)
+        .filter(|to| {
+            let d2 = to.length_squared();
+            d2 > 0.0 && d2 <= range_sq && heading.dot(*to) >= min_cos * d2.sqrt()
+        })
+        .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+}
+
+/// Rotate `vel` toward `to_target` by at most `max_turn` radians, keeping speed.
+#[inline]
+pub fn steer(vel: Vec2, to_target: Vec2, max_turn: f32) -> Vec2 {
+    let angle = vel.angle_to(to_target);
+    Vec2::from_angle(angle.clamp(-max_turn, max_turn)).rotate(vel)
+}
+
+pub fn steer_homing_bullets(
+    time: Res<Time<Fixed>>,
+    player_e: Res<PlayerEntity>,
+    q_enemies: Query<(&Position, &CollisionLayers), With<Enemy>>,
+    q_player: Query<&Position, Without<PooledBullet>>,
+    mut q_bullets: Query<(&Bullet, &BulletState, &Position, &mut LinearVelocity), With<PooledBullet>>,
+) {
+    let dt = time.delta_secs();
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let player_pos = q_player.get(player).expect("PlayerEntity invalid").0;
+
+    for (bullet, state, pos, mut vel) in &mut q_bullets {
+        if *state != BulletState::Active { continue; }
+        let Some(homing) = bullet.homing else { continue; };
+        let Some(heading) = vel.0.try_normalize() else { continue; };
+
+        let target = match bullet.kind {
+            // Dying enemies clear their filters, so they stop attracting bullets too.
+            BulletKind::Player => acquire(
+                &homing,
+                pos.0,
+                heading,
+                q_enemies
+                    .iter()
+                    .filter(|(_, layers)| layers.filters.has_all(Layer::PlayerBullet))
+                    .map(|(p, _)| p.0),
+            ),
+            BulletKind::Enemy => acquire(&homing, pos.0, heading, [player_pos]),
+        };
+
+        if let Some(to_target) = target {
+            vel.0 = steer(vel.0, to_target, homing.turn_rate_deg.to_radians() * dt);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Bullet expiry: lifetime, travel range and arena escape.
+//!
+//! This system never recycles anything itself. It only marks `PendingReturn`, so
+//! `return_to_pool_commit` stays the single owner of the Inactive invariants.
+//!
+//! # Why `Position` instead of `Transform`?
+//! This runs after the physics step; Avian's `Position` is already up to date there,
+//! while `Transform` is only written back later in the schedule.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::plugins::world::ArenaBounds;
+
+use super::components::{Bullet, BulletState, PooledBullet};
+
+/// How far past the wall faces a bullet may be before it counts as escaped.
+/// Must exceed wall thickness so bullets mid-bounce are not culled.
+pub const ARENA_ESCAPE_MARGIN: f32 = 64.0;
+
+pub fn expire_bullets(
+    time: Res<Time<Fixed>>,
+    bounds: Res<ArenaBounds>,
+    mut q: Query<(&mut Bullet, &mut BulletState, &Position, &LinearVelocity), With<PooledBullet>>,
+) {
+    // Fixed time follows virtual time, so hitstop/slowmo stretch lifetimes too.
+    let dt = time.delta_secs();
+
+    for (mut bullet, mut state, pos, vel) in &mut q {
+        if *state != BulletState::Active { continue; }
+
+        let expired = bullet.age(dt, vel.0.length() * dt);
+        let escaped = !bounds.contains_with_margin(pos.0, ARENA_ESCAPE_MARGIN);
+
+        if expired || escaped {
+            *state = BulletState::PendingReturn;
+        }
+    }
+}
@@ -8,6 +8,12 @@
 //! This is a producer → queue → consumer pipeline.
 
 use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::plugins::combat::status::StatusApply;
+use crate::plugins::combat::{DamageRequest, DamageType};
+
+use super::components::Bullet;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BulletKind {
@@ -15,11 +21,268 @@ pub enum BulletKind {
     Enemy,
 }
 
+/// Wall ricochet control.
+///
+/// Bullets are sensors, so nothing bounces "for free": the resolve step reflects
+/// velocity off the wall face explicitly.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Ricochet {
+    /// Wall reflections allowed; the next wall hit after that returns the bullet.
+    pub bounces: u8,
+    /// Fraction of speed kept per reflection.
+    pub speed_retain: f32,
+}
+
+impl Default for Ricochet {
+    fn default() -> Self {
+        Self { bounces: Bullet::DEFAULT_WALL_BOUNCES, speed_retain: 0.95 }
+    }
+}
+
+/// Homing steering parameters.
+///
+/// Opt-in per shot (`BulletModifiers::homing`): the pooled entity layout never changes,
+/// the steering system simply skips bullets whose `homing` is `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Homing {
+    /// Maximum heading change in degrees per second.
+    pub turn_rate_deg: f32,
+    /// Full acquisition cone around the current heading, in degrees.
+    pub cone_deg: f32,
+    /// Targets further than this (pixels) are ignored.
+    pub range: f32,
+}
+
+impl Default for Homing {
+    fn default() -> Self {
+        Self { turn_rate_deg: 180.0, cone_deg: 90.0, range: 600.0 }
+    }
+}
+
+/// Area-of-effect payload detonated when the bullet is returned to the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Explosive {
+    /// Blast radius in pixels.
+    pub radius: f32,
+    /// Damage at the centre of the blast.
+    pub damage: i32,
+    /// Fraction of `damage` (and `impulse`) left at the rim.
+    pub edge_falloff: f32,
+    /// Velocity change in px/s at the centre for a body of unit mass.
+    pub impulse: f32,
+    /// Also detonate when lifetime/range runs out (false = fizzle).
+    pub on_expiry: bool,
+    pub damage_type: DamageType,
+}
+
+impl Default for Explosive {
+    fn default() -> Self {
+        Self {
+            radius: 96.0,
+            damage: 3,
+            edge_falloff: 0.25,
+            impulse: 400.0,
+            on_expiry: true,
+            damage_type: DamageType::Explosive,
+        }
+    }
+}
+
+/// Per-shot behaviour modifiers. Any producer can set these.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BulletModifiers {
+    /// Damage type of direct hits (resolved against the target's `Resistances`).
+    pub damage_type: DamageType,
+    /// Extra enemies the bullet passes through (0 = consumed by the first hit).
+    pub pierce: u8,
+    /// Skip the `Armour` gate and damage `Health` directly.
+    pub armour_piercing: bool,
+    pub ricochet: Ricochet,
+    /// Steer toward the nearest valid target (`None` = straight line).
+    pub homing: Option<Homing>,
+    /// Detonate on impact (and optionally on expiry).
+    pub explosive: Option<Explosive>,
+    /// Impulse (px/s at weight 1) pushed into whatever the bullet hits.
+    pub knockback: f32,
+    /// Status effect applied by direct hits that armour does not block.
+    pub status: Option<StatusApply>,
+    /// Enemy bullets only: player bullets can shoot it down.
+    pub destructible: bool,
+}
+
+impl Default for BulletModifiers {
+    fn default() -> Self {
+        Self {
+            damage_type: DamageType::Kinetic,
+            pierce: 0,
+            armour_piercing: false,
+            ricochet: Ricochet::default(),
+            homing: None,
+            explosive: None,
+            knockback: 80.0,
+            status: None,
+            destructible: false,
+        }
+    }
+}
+
 #[derive(Message, Clone, Copy, Debug)]
 pub struct SpawnBulletRequest {
     pub kind: BulletKind,
     pub pos: Vec2,
     pub vel: Vec2,
     pub damage: i32,
+    pub modifiers: BulletModifiers,
+    /// Seconds of virtual time before the bullet expires.
+    pub lifetime: f32,
+    /// Travel distance in pixels before the bullet expires.
+    pub max_range: f32,
+    pub owner: Option<Entity>,
+    /// Uniform size of the sprite and collider (1 = a `Bullet::RADIUS` bullet).
+    pub scale: f32,
+    /// Fixed-clock time (`Time<Fixed>::elapsed_secs`) the shot was due, for requests that
+    /// wait for the allocator (emitters). `None` spawns at `pos` as is.
+    pub fired_at: Option<f32>,
+}
+
+impl SpawnBulletRequest {
+    /// The request as if the bullet had been flying since `fired_at`, with the fixed
+    /// clock now at `now`.
+    #[inline]
+    pub fn caught_up(mut self, now: f32) -> Self {
+        if let Some(at) = self.fired_at {
+            self.pos += self.vel * (now - at).max(0.0);
+        }
+        self
+    }
+}
+
+/// Cancel every active enemy bullet within `radius` of `pos`, converting each into a
+/// score pickup.
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct BulletCancel {
+    pub pos: Vec2,
+    pub radius: f32,
+}
+
+/// An enemy bullet passed the player inside the graze zone without hitting.
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct Grazed {
+    /// Bullet position when it left the zone.
+    pub pos: Vec2,
+    pub points: u32,
+}
+
+/// The player collected a score pickup.
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct ScoreCollected {
+    pub value: u32,
+    pub pos: Vec2,
+}
+
+/// The player fired (one per trigger pull or beam tick, whatever the pellet count).
+///
+/// Written by the player producer; enemies within their hearing radius notice it (see
+/// `enemies::perception`).
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct Gunshot {
+    pub pos: Vec2,
+}
+
+/// One instant ray (a hitscan pellet or one beam tick).
+///
+/// Written by the player producer, traced in the fixed step by `hitscan::resolve_hitscan`.
+/// Of the modifiers, rays use damage type, pierce, armour piercing, knockback and status;
+/// wall bounces come from `bounces`, and homing/explosive payloads are ignored.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct HitscanRequest {
+    pub origin: Vec2,
+    /// Normalized direction.
+    pub dir: Vec2,
+    /// Total ray length in pixels, summed over every bounce.
+    pub range: f32,
+    /// Wall reflections allowed.
+    pub bounces: u8,
+    pub damage: i32,
+    pub modifiers: BulletModifiers,
+    pub owner: Option<Entity>,
+    /// Continuous beam tick (affects presentation only).
+    pub beam: bool,
+}
+
+impl HitscanRequest {
+    /// Ray hit on `target` at `point`, travelling along `dir`.
+    pub fn damage_request(&self, target: Entity, point: Vec2, dir: Vec2) -> DamageRequest {
+        DamageRequest {
+            source: self.owner,
+            target,
+            amount: self.damage,
+            damage_type: self.modifiers.damage_type,
+            position: point,
+            impulse: dir.normalize_or_zero() * self.modifiers.knockback,
+            armour_piercing: self.modifiers.armour_piercing,
+            status: self.modifiers.status,
+            periodic: false,
+        }
+    }
+}
+
+/// A detonation, written once per exploding bullet.
+///
+/// Consumed by the splash resolve (which turns it into `DamageRequest`s);
+/// presentation (shake/flash) subscribes too.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct Explosion {
+    /// Kind of the bullet that exploded; decides who the blast can hurt.
+    pub kind: BulletKind,
+    /// Who fired the bullet (damage `source`).
     pub owner: Option<Entity>,
+    pub pos: Vec2,
+    pub radius: f32,
+    pub damage: i32,
+    pub damage_type: DamageType,
+    pub edge_falloff: f32,
+    pub impulse: f32,
+    pub armour_piercing: bool,
+}
+
+impl Explosion {
+    /// Falloff factor in [edge_falloff, 1] for a target `distance` pixels from the centre.
+    #[inline]
+    pub fn falloff_at(&self, distance: f32) -> f32 {
+        let t = (distance / self.radius.max(f32::EPSILON)).clamp(0.0, 1.0);
+        1.0 + (self.edge_falloff - 1.0) * t
+    }
+
+    /// Splash damage at `distance`; anything inside the radius takes at least 1.
+    #[inline]
+    pub fn damage_at(&self, distance: f32) -> i32 {
+        ((self.damage as f32 * self.falloff_at(distance)).round() as i32).max(1)
+    }
+
+    /// Velocity change for a unit-mass body at `target`, pointing away from the centre.
+    #[inline]
+    pub fn impulse_at(&self, target: Vec2) -> Vec2 {
+        let offset = target - self.pos;
+        offset.normalize_or_zero() * self.impulse * self.falloff_at(offset.length())
+    }
+
+    /// Splash hit on `target` standing at `target_pos`.
+    pub fn damage_request(&self, target: Entity, target_pos: Vec2) -> DamageRequest {
+        DamageRequest {
+            source: self.owner,
+            target,
+            amount: self.damage_at(target_pos.distance(self.pos)),
+            damage_type: self.damage_type,
+            position: self.pos,
+            impulse: self.impulse_at(target_pos),
+            armour_piercing: self.armour_piercing,
+            status: None,
+            periodic: false,
+        }
+    }
 }
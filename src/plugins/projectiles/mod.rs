@@ -13,6 +13,14 @@
 //!
 //! # Data flow (big picture)
 //! ```text
+//!   FixedUpdate (fixed dt)
+//!┌────────────────────────────────────────────────────────────────────────────┐
+//!│  (P) Enemy producer: step_emitters                                         │
+//!│      - reads: Emitter + BulletPattern asset, player Transform              │
+//!│      - writes: SpawnBulletRequest { kind: Enemy, .. } message(s)           │
+//!└────────────────────────────────────────────────────────────────────────────┘
+//!                │
+//!                v
 //!   Update schedule (variable dt)
 //!┌────────────────────────────────────────────────────────────────────────────┐
 //!│  (A) Aim Update (normalize cursor → world space)                           │
@@ -21,46 +29,99 @@
 //!│                                                                            │
 //!│  (B) Producer: request_player_bullets                                      │
 //!│      - reads: MouseButton input, PlayerEntity, Aim, Player Transform       │
-//!│      - writes: SpawnBulletRequest message                                  │
+//!│      - reads: Weapon (data-driven WeaponDef: pellets, spread, speed, ...)  │
+//!│      - mutates: WeaponState (cooldown, magazine, reload; Time<Virtual>)    │
+//!│      - charge weapons: hold builds WeaponState.charge_secs, release fires  │
+//!│      - writes: SpawnBulletRequest message(s)                               │
+//!│      - writes: HitscanRequest message(s) (hitscan / beam delivery)         │
+//!│      - writes: one Gunshot message per shot (heard by enemy perception)    │
 //!│                                                                            │
 //!│  (C) Consumer: allocate_bullets_from_pool                                  │
-//!│      - reads: SpawnBulletRequest messages                                  │
-//!│      - mutates: BulletPool.free (Vec<BulletEntity>)                        │
-//!│      - mutates: BulletState, Bullet, Transform, Velocity, Visibility,      │
-//!│                 CollisionLayers                                            │
+//!│      - reads: SpawnBulletRequest messages (player fire + emitters)         │
+//!│      - reads: Time<Fixed> (emitter volleys catch up to their due time)     │
+//!│      - mutates: BulletPools (per-kind free lists + overflow policy)        │
+//!│      - mutates: BulletState, Bullet, Transform (incl. per-shot scale),     │
+//!│                 Velocity, Visibility, CollisionLayers                      │
 //!└────────────────────────────────────────────────────────────────────────────┘
 //!                │
 //!                v
 //!FixedPostUpdate (fixed dt)
 //!┌────────────────────────────────────────────────────────────────────────────┐
+//!│  (H) Steer: steer_homing_bullets (before PhysicsSystems::StepSimulation)   │
+//!│      - reads: Bullet.homing, enemy/player Position                         │
+//!│      - mutates: LinearVelocity (turn-rate limited, speed preserved)        │
+//!│                                                                            │
 //!│  (D) Physics emits CollisionStart messages (Avian)                         │
 //!│                                                                            │
 //!│  (E) Resolve collisions: process_player_bullet_collisions                  │
 //!│      - reads: CollisionStart messages                                      │
-//!│      - reads: layers/armour/health                                         │
+//!│      - reads: layers, wall ColliderAabb                                    │
+//!│      - mutates: Position/LinearVelocity (explicit ricochet reflection)     │
+//!│      - writes: DamageRequest (combat::apply_damage owns armour/health)     │
+//!│      - mutates: pierce budget                                              │
 //!│      - mutates: BulletState -> PendingReturn                               │
-//!│      - dedupe: CollisionStamp + CollisionEpoch (no HashSet)                │
+//!│      - dedupe: CollisionStamp (epoch per tick, hit targets per flight)     │
+//!│                                                                            │
+//!│  (E2) Resolve enemy fire: process_enemy_bullet_collisions                  │
+//!│      - reads: CollisionStart messages                                      │
+//!│      - writes: DamageRequest (i-frames enforced by combat::apply_damage)   │
+//!│      - mutates: BulletState -> PendingReturn                               │
+//!│                                                                            │
+//!│  (R) Rays: hitscan::resolve_hitscan (after PhysicsSystems::StepSimulation) │
+//!│      - reads: HitscanRequest messages (buffer advanced on the fixed clock) │
+//!│      - reads: SpatialQuery::cast_ray (World | Enemy), reflects off World   │
+//!│      - writes: DamageRequest per enemy crossed (pierce budget per ray)     │
+//!│      - mutates: RayFx tracers (drawn + faded in Update)                    │
+//!│                                                                            │
+//!│  (E4) Shoot-down: process_bullet_vs_bullet_collisions                      │
+//!│      - reads: CollisionStart (PlayerBullet × destructible EnemyBullet)     │
+//!│      - mutates: both BulletStates -> PendingReturn (pierce spends one)     │
+//!│                                                                            │
+//!│  (E3) Expire: expire_bullets                                               │
+//!│      - reads: Time<Fixed>, ArenaBounds, Position, LinearVelocity           │
+//!│      - mutates: Bullet lifetime/range budgets                              │
+//!│      - mutates: BulletState -> PendingReturn (expired or escaped arena)    │
+//!│                                                                            │
+//!│  (N) Graze: detect_grazes (after enemy fire resolve + expiry)              │
+//!│      - reads: player Position + GrazeZone, SpatialQuery (EnemyBullet)      │
+//!│      - mutates: CollisionStamp.graze, GrazeCounter; writes: Grazed         │
+//!│                                                                            │
+//!│  (X) Detonate + splash: detonate_explosives -> apply_explosions            │
+//!│      - reads: PendingReturn bullets carrying an Explosive payload          │
+//!│      - writes: Explosion message (GlobalFx presets subscribe)              │
+//!│      - reads: SpatialQuery (circle overlap, Layer filter per bullet kind)  │
+//!│      - writes: DamageRequest per target (falloff damage + impulse)         │
+//!│                                                                            │
+//!│  (K) Cancel: apply_bullet_cancels (after detonation)                       │
+//!│      - reads: BulletCancel messages, enemy bullet Position                 │
+//!│      - mutates: BulletState -> PendingReturn, spawns ScorePickup           │
+//!│                                                                            │
+//!│  (G) Apply damage: combat::apply_damage (combat plugin)                    │
+//!│      - reads: DamageRequest messages                                       │
+//!│      - mutates: Armour/Health/Invulnerability/Knockback                    │
+//!│      - writes: DamageEvent (outcome; FX/score subscribe)                   │
 //!│                                                                            │
 //!│  (F) Commit returns: return_to_pool_commit                                 │
 //!│      - reads: bullets with PendingReturn                                   │
 //!│      - writes invariants for Inactive state                                │
-//!│      - mutates: BulletPool.free.push(BulletEntity)                         │
+//!│      - mutates: BulletPools[kind].free.push(BulletEntity)                  │
 //!└────────────────────────────────────────────────────────────────────────────┘
 //!
 //! Feedback loop:
-//!   commit pushes BulletEntity back into BulletPool.free
-//!   allocator pops BulletEntity from BulletPool.free
+//!   commit pushes BulletEntity back into BulletPools[kind].free
+//!   allocator pops BulletEntity from BulletPools[kind].free
 //! ```
 //!
 //! # Why "Messages" instead of direct pool access?
-//! Producers do **not** borrow `ResMut<BulletPool>`.
+//! Producers do **not** borrow `ResMut<BulletPools>`.
 //! They only enqueue intent (SpawnBulletRequest).
 //! The allocator is the **single writer** that mutates the pool.
 //! This improves decoupling and keeps pool mutation localized.
 //!
 //! # Where do we still branch?
 //! - Real-world input: cursor can be missing (outside window) → Aim becomes None.
-//! - Capacity: pool can be empty → allocator drops request (capacity decision).
+//! - Capacity: pool can be empty → allocator applies the pool's `OverflowPolicy`
+//!   (drop newest / recycle oldest / grow in chunks).
 //! Everything else is treated as an invariant violation.
 //! BulletState (explicit enum)
 
@@ -68,6 +129,12 @@ pub mod layers;
 pub mod components;
 pub mod pool;
 pub mod collision;
+pub mod lifetime;
+pub mod homing;
+pub mod explosion;
+pub mod hitscan;
+pub mod cancel;
+pub mod graze;
 
 // v3 message-based spawn pipeline
 pub mod messages;
@@ -75,10 +142,16 @@ pub mod request;
 pub mod allocator;
 pub mod commit;
 
+// data-driven weapons + enemy bullet patterns
+pub mod weapon;
+pub mod emitter;
+
 use bevy::prelude::*;
 use bevy::ecs::message::Messages;
 use avian2d::collision::narrow_phase::CollisionEventSystems;
+use avian2d::prelude::PhysicsSystems;
 
+use crate::common::ron_asset::register_ron_asset;
 use crate::common::state::GameState;
 
 pub struct ProjectilesPlugin;
@@ -90,17 +163,83 @@ fn update_spawn_messages(mut msgs: ResMut<Messages<messages::SpawnBulletRequest>
     msgs.update();
 }
 
+/// Maintain hitscan message buffers on the fixed clock.
+///
+/// Rays are requested in `Update` but traced in the fixed step; advancing the buffer
+/// after the trace (instead of in `PostUpdate`) means no shot is lost to a frame that
+/// ran no fixed step.
+fn update_hitscan_messages(mut msgs: ResMut<Messages<messages::HitscanRequest>>) {
+    msgs.update();
+}
+
+/// Maintain explosion message buffers (written and read within the fixed step).
+fn update_explosion_messages(mut msgs: ResMut<Messages<messages::Explosion>>) {
+    msgs.update();
+}
+
+/// Maintain bullet-cancel, pickup and graze message buffers (written and read within the
+/// fixed step).
+fn update_score_messages(
+    mut cancels: ResMut<Messages<messages::BulletCancel>>,
+    mut collected: ResMut<Messages<messages::ScoreCollected>>,
+    mut grazed: ResMut<Messages<messages::Grazed>>,
+) {
+    cancels.update();
+    collected.update();
+    grazed.update();
+}
+
 impl Plugin for ProjectilesPlugin {
     fn build(&self, app: &mut App) {
         // Pool + pre-spawn
-        app.insert_resource(pool::BulletPool::new(512))
+        app.insert_resource(pool::BulletPools::default())
             .insert_resource(components::CollisionEpoch::default())
             .insert_resource(components::Aim::default())
             .add_systems(Startup, pool::init_bullet_pool);
 
+        // Weapon definitions (RON assets) + the loadout the player cycles through.
+        register_ron_asset::<weapon::WeaponDef>(app);
+        app.init_resource::<weapon::WeaponLoadout>()
+            .add_systems(Startup, weapon::load_default_loadout)
+            .add_systems(OnEnter(GameState::InGame), weapon::reset_loadout_states)
+            .add_systems(
+                Update,
+                (
+                    weapon::cycle_weapon,
+                    weapon::sync_equipped_weapon.after(weapon::cycle_weapon),
+                    weapon::update_weapon_state.after(weapon::sync_equipped_weapon),
+                )
+                    .before(request::request_player_bullets)
+                    .run_if(in_state(GameState::InGame)),
+            );
+
+        // Bullet patterns (RON assets) stepped on the fixed timestep for determinism.
+        register_ron_asset::<emitter::BulletPattern>(app);
+        app.add_systems(
+            FixedUpdate,
+            emitter::step_emitters.run_if(in_state(GameState::InGame)),
+        );
+
         // Message storage for spawn requests.
         app.init_resource::<Messages<messages::SpawnBulletRequest>>();
-        app.add_systems(PostUpdate, update_spawn_messages);
+        app.init_resource::<Messages<messages::Explosion>>();
+        app.init_resource::<Messages<messages::HitscanRequest>>();
+        app.init_resource::<Messages<messages::BulletCancel>>();
+        app.init_resource::<Messages<messages::ScoreCollected>>();
+        app.init_resource::<Messages<messages::Grazed>>();
+        // Advanced by the enemies plugin, after perception has listened (fixed clock).
+        app.init_resource::<Messages<messages::Gunshot>>();
+        app.add_systems(
+            PostUpdate,
+            (update_spawn_messages, update_explosion_messages, update_score_messages),
+        );
+
+        // Ray presentation (hitscan tracers + beams).
+        app.init_resource::<hitscan::RayFx>().add_systems(
+            Update,
+            (hitscan::fade_ray_fx, hitscan::draw_ray_fx.after(hitscan::fade_ray_fx))
+                .run_if(in_state(GameState::InGame)),
+        );
 
         // Update-phase pipeline: aim -> request -> allocate
         app.add_systems(
@@ -118,6 +257,14 @@ impl Plugin for ProjectilesPlugin {
                 .run_if(in_state(GameState::InGame)),
         );
 
+        // Fixed steering (before the solver integrates velocities)
+        app.add_systems(
+            FixedPostUpdate,
+            homing::steer_homing_bullets
+                .before(PhysicsSystems::StepSimulation)
+                .run_if(in_state(GameState::InGame)),
+        );
+
         // Fixed collision pipeline
         app.add_systems(
             FixedPostUpdate,
@@ -125,11 +272,81 @@ impl Plugin for ProjectilesPlugin {
                 .after(CollisionEventSystems)
                 .run_if(in_state(GameState::InGame)),
         )
+        .add_systems(
+            FixedPostUpdate,
+            collision::process_enemy_bullet_collisions
+                .after(collision::process_player_bullet_collisions)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            collision::process_bullet_vs_bullet_collisions
+                .after(collision::process_enemy_bullet_collisions)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            (
+                hitscan::resolve_hitscan,
+                update_hitscan_messages.after(hitscan::resolve_hitscan),
+            )
+                .after(PhysicsSystems::StepSimulation)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            lifetime::expire_bullets
+                .after(collision::process_enemy_bullet_collisions)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            (
+                explosion::detonate_explosives,
+                explosion::apply_explosions.after(explosion::detonate_explosives),
+            )
+                .after(collision::process_enemy_bullet_collisions)
+                .after(collision::process_bullet_vs_bullet_collisions)
+                .after(lifetime::expire_bullets)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedPostUpdate,
+            cancel::apply_bullet_cancels
+                .after(explosion::detonate_explosives)
+                .run_if(in_state(GameState::InGame)),
+        )
         .add_systems(
             FixedPostUpdate,
             commit::return_to_pool_commit
                 .after(collision::process_player_bullet_collisions)
+                .after(collision::process_enemy_bullet_collisions)
+                .after(collision::process_bullet_vs_bullet_collisions)
+                .after(lifetime::expire_bullets)
+                .after(explosion::detonate_explosives)
+                .after(cancel::apply_bullet_cancels)
                 .run_if(in_state(GameState::InGame)),
         );
+
+        // Near misses, counted after enemy fire resolved (a hit is never a graze).
+        app.init_resource::<graze::GrazeCounter>()
+            .add_systems(OnEnter(GameState::InGame), graze::reset_graze_counter)
+            .add_systems(
+                FixedPostUpdate,
+                graze::detect_grazes
+                    .after(collision::process_enemy_bullet_collisions)
+                    .after(lifetime::expire_bullets)
+                    .before(commit::return_to_pool_commit)
+                    .run_if(in_state(GameState::InGame)),
+            );
+
+        // Score pickups from cancelled bullets.
+        app.add_systems(
+            FixedUpdate,
+            cancel::collect_score_pickups.run_if(in_state(GameState::InGame)),
+        );
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -4,26 +4,61 @@
 //! - `BulletPool.free` stores only `BulletEntity` (typed free list).
 //! - pooled bullet entities are spawned once and never despawned individually.
 //! - "inactive" bullets are hidden, have zero velocity, and collide with nothing.
+//! - every pooled bullet belongs to exactly one pool: the one matching `Bullet::kind`.
+//!
+//! # Overflow
+//! Each `BulletKind` has its own pool, so enemy bullet-hell patterns cannot starve the
+//! player's gun (and vice versa). When a pool's free list is empty the allocator applies
+//! that pool's `OverflowPolicy`:
+//! - `DropNewest`: the request is discarded.
+//! - `RecycleOldest`: the oldest *active* bullet of that kind is re-fired for the request.
+//! - `Grow`: spawn `chunk` more pooled entities (up to `max_capacity`), then fall back to
+//!   `DropNewest` once the ceiling is reached.
 //!
 //! # Performance
 //! - pooling avoids spawn/despawn churn
 //! - Option A disable (collision filters empty) avoids structural enable/disable toggles
+//! - growing is the only structural change, and it happens in chunks
+//!
+//! # Sensors
+//! Pooled bullets are sensor colliders: they report `CollisionStart` but get no contact
+//! response. Piercing is a real pass-through, and wall ricochet is an explicit velocity
+//! reflection in the resolve step (see `collision.rs`) rather than a restitution side
+//! effect. Sensors contribute no mass, so the body gets an explicit `Mass`.
+//!
+//! # Size
+//! Every pooled bullet has the same `Collider::circle(Bullet::RADIUS)`. Per-shot size
+//! (charge shots) is a `Transform::scale` written on activation; Avian scales the
+//! collider from the transform and the sprite follows it, so resizing never touches
+//! the collider component or despawns the entity.
 
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
 use super::components::{Bullet, BulletEntity, BulletState, CollisionStamp, PooledBullet};
 use super::layers::Layer;
+use super::messages::BulletKind;
 
-#[derive(Resource, Debug)]
+/// What the allocator does when a pool has no free bullets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    DropNewest,
+    RecycleOldest,
+    Grow { chunk: usize, max_capacity: usize },
+}
+
+#[derive(Debug)]
 pub struct BulletPool {
+    pub kind: BulletKind,
     pub free: Vec<BulletEntity>,
+    /// Number of pooled entities owned by this pool (free + in flight).
     pub capacity: usize,
+    pub policy: OverflowPolicy,
 }
 
 impl BulletPool {
-    pub fn new(capacity: usize) -> Self {
-        Self { free: Vec::with_capacity(capacity), capacity }
+    pub fn new(kind: BulletKind, capacity: usize, policy: OverflowPolicy) -> Self {
+        Self { kind, free: Vec::with_capacity(capacity), capacity, policy }
     }
 
     #[inline]
@@ -35,11 +70,63 @@ impl BulletPool {
     pub fn push_free(&mut self, e: BulletEntity) {
         self.free.push(e)
     }
+
+    /// How many entities a `Grow` policy may add right now (0 for other policies).
+    #[inline]
+    pub fn grow_amount(&self) -> usize {
+        match self.policy {
+            OverflowPolicy::Grow { chunk, max_capacity } => {
+                chunk.min(max_capacity.saturating_sub(self.capacity))
+            }
+            _ => 0,
+        }
+    }
 }
 
+/// One pool per `BulletKind`.
+#[derive(Resource, Debug)]
+pub struct BulletPools {
+    pub player: BulletPool,
+    pub enemy: BulletPool,
+    /// Source of `Bullet::activation` stamps (shared so stamps are globally ordered).
+    pub next_activation: u32,
+}
+
+impl Default for BulletPools {
+    fn default() -> Self {
+        Self {
+            player: BulletPool::new(BulletKind::Player, 256, OverflowPolicy::RecycleOldest),
+            enemy: BulletPool::new(
+                BulletKind::Enemy,
+                1024,
+                OverflowPolicy::Grow { chunk: 256, max_capacity: 4096 },
+            ),
+            next_activation: 0,
+        }
+    }
+}
+
+impl BulletPools {
+    #[inline]
+    pub fn get_mut(&mut self, kind: BulletKind) -> &mut BulletPool {
+        match kind {
+            BulletKind::Player => &mut self.player,
+            BulletKind::Enemy => &mut self.enemy,
+        }
+    }
+
+    #[inline]
+    pub fn next_activation(&mut self) -> u32 {
+        self.next_activation = self.next_activation.wrapping_add(1);
+        self.next_activation
+    }
+}
+
+/// Player bullets also filter `EnemyBullet`; since Avian needs *both* sides to accept
+/// a pair, only enemy bullets that opt in (`active_destructible_enemy_layers`) collide.
 #[inline]
 pub fn active_player_layers() -> CollisionLayers {
-    CollisionLayers::new(Layer::PlayerBullet, [Layer::World, Layer::Enemy])
+    CollisionLayers::new(Layer::PlayerBullet, [Layer::World, Layer::Enemy, Layer::EnemyBullet])
 }
 
 #[inline]
@@ -47,42 +134,71 @@ pub fn active_enemy_layers() -> CollisionLayers {
     CollisionLayers::new(Layer::EnemyBullet, [Layer::World, Layer::Player])
 }
 
+/// Enemy bullets that player bullets can shoot down.
+#[inline]
+pub fn active_destructible_enemy_layers() -> CollisionLayers {
+    CollisionLayers::new(Layer::EnemyBullet, [Layer::World, Layer::Player, Layer::PlayerBullet])
+}
+
+#[inline]
+pub fn active_layers(bullet: &Bullet) -> CollisionLayers {
+    match bullet.kind {
+        BulletKind::Player => active_player_layers(),
+        BulletKind::Enemy if bullet.destructible => active_destructible_enemy_layers(),
+        BulletKind::Enemy => active_enemy_layers(),
+    }
+}
+
 #[inline]
 pub fn inactive_bullet_layers() -> CollisionLayers {
     CollisionLayers::new(Layer::PlayerBullet, [] as [Layer; 0])
 }
 
-pub fn init_bullet_pool(mut commands: Commands, mut pool: ResMut<BulletPool>) {
-    pool.free.clear();
-    let cap = pool.capacity;
-    pool.free.reserve(cap);
+/// Everything a pooled bullet entity is spawned with (Inactive state).
+///
+/// Shared by the startup pre-spawn and the allocator's grow path so both produce
+/// identical entities.
+pub fn pooled_bullet_bundle(kind: BulletKind) -> impl Bundle {
+    let color = match kind {
+        BulletKind::Player => Color::srgb(1.0, 0.85, 0.3),
+        BulletKind::Enemy => Color::srgb(1.0, 0.35, 0.65),
+    };
 
-    let restitution = Restitution::new(0.95).with_combine_rule(CoefficientCombine::Max);
-    let friction = Friction::ZERO;
-
-    for _ in 0..cap {
-        let e = commands.spawn((
+    (
+        (
             Name::new("Bullet(Pooled)"),
             PooledBullet,
             BulletState::Inactive,
-            Bullet { damage: 1, wall_bounces_left: Bullet::DEFAULT_WALL_BOUNCES },
+            Bullet::pooled(kind),
             CollisionStamp::default(),
-            Sprite {
-                color: Color::srgb(1.0, 0.85, 0.3),
-                custom_size: Some(Vec2::splat(8.0)),
-                ..default()
-            },
-            Transform::from_xyz(0.0, 0.0, 2.0),
-            Visibility::Hidden,
-            RigidBody::Dynamic,
-            Collider::circle(4.0),
-            inactive_bullet_layers(),
-            restitution,
-            friction,
-            LinearVelocity(Vec2::ZERO),
-            CollisionEventsEnabled,
-        )).id();
-
-        pool.push_free(BulletEntity(e));
+        ),
+        Sprite {
+            color,
+            custom_size: Some(Vec2::splat(8.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 2.0),
+        Visibility::Hidden,
+        RigidBody::Dynamic,
+        Collider::circle(Bullet::RADIUS),
+        Sensor,
+        Mass(1.0),
+        inactive_bullet_layers(),
+        LinearVelocity(Vec2::ZERO),
+        CollisionEventsEnabled,
+    )
+}
+
+pub fn init_bullet_pool(mut commands: Commands, mut pools: ResMut<BulletPools>) {
+    let BulletPools { player, enemy, .. } = &mut *pools;
+    for pool in [player, enemy] {
+        pool.free.clear();
+        let cap = pool.capacity;
+        pool.free.reserve(cap);
+
+        for _ in 0..cap {
+            let e = commands.spawn(pooled_bullet_bundle(pool.kind)).id();
+            pool.push_free(BulletEntity(e));
+        }
     }
 }
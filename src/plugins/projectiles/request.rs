@@ -6,19 +6,35 @@
 //! everywhere we need it.
 //!
 //! # Runtime checks we keep
-//! - The cursor may be outside the window → Aim becomes None.
+//! - The cursor may be outside the window → Aim becomes None (no shot; with hold-to-fire
+//!   the button can stay down while the cursor leaves the window).
 //!
 //! # Runtime checks we remove
 //! - Re-discovering camera/player each click (architecture checks).
 //!   We store `PlayerEntity` and `MainCameraEntity` once at spawn time.
+//!
+//! # What a shot looks like
+//! The equipped `Weapon` decides pellets, spread, speed, damage and muzzle offset;
+//! this producer only supplies origin + aim direction. Automatic and beam weapons fire
+//! while the button is held; `WeaponState::try_fire` gates cadence and ammo.
+//!
+//! Projectile weapons write `SpawnBulletRequest`s; hitscan and beam weapons write
+//! `HitscanRequest`s, traced in the fixed step. Every shot also writes one `Gunshot`,
+//! which nearby enemies hear.
+//!
+//! Charge weapons replace the trigger rule: holding builds charge on the weapon's clock,
+//! and the *release* fires with the requests scaled by the charge level.
 
 use bevy::prelude::*;
 use bevy::ecs::message::MessageWriter;
+use bevy::ecs::system::SystemParam;
+use bevy::time::{Real, Virtual};
 
-use crate::common::tunables::Tunables;
+use crate::plugins::score::RunStats;
 
 use super::components::{Aim, MainCameraEntity, PlayerEntity};
-use super::messages::{BulletKind, SpawnBulletRequest};
+use super::messages::{BulletKind, Gunshot, HitscanRequest, SpawnBulletRequest};
+use super::weapon::{ChargeClock, Delivery, Weapon, WeaponState};
 
 pub fn update_aim_from_cursor(
     windows: Query<&Window>,
@@ -39,22 +55,69 @@ pub fn update_aim_from_cursor(
     aim.world_cursor = camera.viewport_to_world_2d(camera_tf, cursor).ok();
 }
 
+/// The clocks a charge can build on (`ChargeClock`).
+#[derive(SystemParam)]
+pub struct ChargeClocks<'w> {
+    virtual_time: Res<'w, Time<Virtual>>,
+    real_time: Res<'w, Time<Real>>,
+}
+
+impl ChargeClocks<'_> {
+    #[inline]
+    fn delta_secs(&self, clock: ChargeClock) -> f32 {
+        match clock {
+            ChargeClock::Virtual => self.virtual_time.delta_secs(),
+            ChargeClock::Real => self.real_time.delta_secs(),
+        }
+    }
+}
+
+/// Everything one shot writes: bullets or rays, plus the gunshot enemies hear.
+#[derive(SystemParam)]
+pub struct ShotWriters<'w> {
+    bullets: MessageWriter<'w, SpawnBulletRequest>,
+    rays: MessageWriter<'w, HitscanRequest>,
+    gunshots: MessageWriter<'w, Gunshot>,
+}
+
 pub fn request_player_bullets(
     buttons: Option<Res<ButtonInput<MouseButton>>>,
-    tunables: Res<Tunables>,
+    clocks: ChargeClocks,
     player_e: Res<PlayerEntity>,
-    q_tf: Query<&Transform>,
+    mut q_player: Query<(&Transform, &Weapon, &mut WeaponState)>,
     aim: Res<Aim>,
-    mut writer: MessageWriter<SpawnBulletRequest>,
+    mut out: ShotWriters,
+    mut stats: ResMut<RunStats>,
 ) {
     let Some(buttons) = buttons else { return; };
-    if !buttons.just_pressed(MouseButton::Left) { return; }
 
-    let player = player_e.0.expect("Clicked but PlayerEntity not set");
-    let player_tf = q_tf.get(player).expect("PlayerEntity invalid");
-    let origin = player_tf.translation.truncate();
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let (player_tf, weapon, mut state) = q_player.get_mut(player).expect("PlayerEntity invalid");
+
+    let charged = match weapon.def.charge {
+        Some(charge) => {
+            if buttons.pressed(MouseButton::Left) {
+                state.hold_charge(&charge, clocks.delta_secs(charge.clock));
+            }
+            if !buttons.just_released(MouseButton::Left) { return; }
+            let Some(level) = state.release_charge(&charge) else { return; };
+            Some((charge, level))
+        }
+        None => {
+            let trigger = if weapon.def.is_held() {
+                buttons.pressed(MouseButton::Left)
+            } else {
+                buttons.just_pressed(MouseButton::Left)
+            };
+            if !trigger { return; }
+            None
+        }
+    };
 
-    let world_cursor = aim.world_cursor.expect("Clicked but Aim.world_cursor is None");
+    let Some(world_cursor) = aim.world_cursor else { return; };
+    if !state.try_fire(&weapon.def) { return; }
+
+    let origin = player_tf.translation.truncate();
 
     let mut dir = world_cursor - origin;
     if dir.length_squared() < 1e-4 {
@@ -62,15 +125,24 @@ pub fn request_player_bullets(
     } else {
         dir = dir.normalize();
     }
+    out.gunshots.write(Gunshot { pos: origin });
 
-    let pos = origin + dir * 18.0;
-    let vel = dir * tunables.bullet_speed;
-
-    writer.write(SpawnBulletRequest {
-        kind: BulletKind::Player,
-        pos,
-        vel,
-        damage: 1,
-        owner: Some(player),
-    });
+    match weapon.def.delivery {
+        Delivery::Projectile => {
+            let reqs = weapon.def.bullet_requests(BulletKind::Player, origin, dir, Some(player));
+            let ids = out.bullets.write_batch(reqs.map(|req| match charged {
+                Some((charge, level)) => charge.scale_bullet(req, level),
+                None => req,
+            }));
+            stats.shots_fired += ids.len() as u32;
+        }
+        Delivery::Hitscan { .. } | Delivery::Beam { .. } => {
+            let reqs = weapon.def.hitscan_requests(origin, dir, Some(player));
+            let ids = out.rays.write_batch(reqs.map(|ray| match charged {
+                Some((charge, level)) => charge.scale_ray(ray, level),
+                None => ray,
+            }));
+            stats.shots_fired += ids.len() as u32;
+        }
+    }
 }
@@ -0,0 +1,1552 @@
+//! Unit tests for the projectiles module.
+//!
+//! Resolve systems are driven by hand-written `CollisionStart` messages, so no physics
+//! step is needed: we only assert the gameplay rules applied on top of the events.
+
+use avian2d::prelude::*;
+use bevy::ecs::message::Messages;
+use bevy::prelude::*;
+
+use crate::common::ron_asset::RonAsset;
+use crate::common::test_utils::{fixed_time_with_delta, run_system_once};
+use crate::plugins::combat::status::{StatusApply, StatusEffects, StatusKind};
+use crate::plugins::combat::{apply_damage, DamageEvent, DamageRequest, DamageType};
+use crate::plugins::score::RunStats;
+
+use super::allocator::allocate_bullets_from_pool;
+use super::cancel::{apply_bullet_cancels, collect_score_pickups, ScorePickup, PICKUP_VALUE};
+use super::collision::{
+    process_bullet_vs_bullet_collisions, process_enemy_bullet_collisions,
+    process_player_bullet_collisions,
+};
+use super::components::*;
+use super::emitter::{step_emitters, BulletPattern, Emitter, PatternShape};
+use super::explosion::detonate_explosives;
+use super::graze::GrazeMark;
+use super::hitscan::{trace, RayContact};
+use super::homing::{acquire, steer, steer_homing_bullets};
+use super::layers::Layer;
+use super::lifetime::expire_bullets;
+use super::messages::{
+    BulletCancel, BulletKind, BulletModifiers, Explosion, Explosive, Gunshot,
+    HitscanRequest, Homing, Ricochet, ScoreCollected, SpawnBulletRequest,
+};
+use super::pool::{
+    active_destructible_enemy_layers, active_enemy_layers, active_layers, init_bullet_pool,
+    BulletPool, BulletPools, OverflowPolicy,
+};
+use super::request::request_player_bullets;
+use super::weapon::{
+    Charge, ChargeClock, Delivery, ReloadState, Weapon, WeaponDef, WeaponLoadout, WeaponState,
+};
+
+// -----------------------------------------------------------------------------
+// Test utilities
+// -----------------------------------------------------------------------------
+
+fn collision_world() -> World {
+    let mut world = World::new();
+    world.init_resource::<Messages<CollisionStart>>();
+    world.insert_resource(CollisionEpoch::default());
+    world.init_resource::<Messages<DamageRequest>>();
+    world.init_resource::<Messages<DamageEvent>>();
+    world.init_resource::<RunStats>();
+    world.insert_resource(fixed_time_with_delta(0.1));
+    world
+}
+
+/// Run a resolve system, then the damage pipeline it feeds.
+///
+/// Consumed requests are cleared so a later `resolve` in the same test doesn't replay them.
+fn resolve<M>(world: &mut World, system: impl IntoSystem<(), (), M>) {
+    run_system_once(world, system);
+    run_system_once(world, apply_damage);
+    world.resource_mut::<Messages<DamageRequest>>().clear();
+}
+
+fn spawn_active_enemy_bullet(world: &mut World, damage: i32) -> Entity {
+    world
+        .spawn((
+            PooledBullet,
+            BulletState::Active,
+            Bullet { damage, knockback: 60.0, ..Bullet::pooled(BulletKind::Enemy) },
+            CollisionStamp::default(),
+            Position(Vec2::ZERO),
+            LinearVelocity(Vec2::new(0.0, -200.0)),
+            active_enemy_layers(),
+        ))
+        .id()
+}
+
+fn spawn_player(world: &mut World, hp: i32) -> Entity {
+    world
+        .spawn((
+            Player,
+            Health { hp },
+            Invulnerability::default(),
+            Knockback::default(),
+            Weight(1.0),
+            CollisionLayers::new(Layer::Player, [Layer::World, Layer::EnemyBullet]),
+        ))
+        .id()
+}
+
+fn start(world: &mut World, a: Entity, b: Entity) {
+    world.resource_mut::<Messages<CollisionStart>>().write(CollisionStart {
+        collider1: a,
+        collider2: b,
+        body1: Some(a),
+        body2: Some(b),
+    });
+}
+
+// -----------------------------------------------------------------------------
+// Enemy fire vs player
+// -----------------------------------------------------------------------------
+
+#[test]
+fn enemy_bullet_damages_player_grants_iframes_and_returns() {
+    let mut world = collision_world();
+    let bullet = spawn_active_enemy_bullet(&mut world, 2);
+    let player = spawn_player(&mut world, 10);
+
+    start(&mut world, bullet, player);
+    resolve(&mut world, process_enemy_bullet_collisions);
+
+    assert_eq!(world.get::<Health>(player).unwrap().hp, 8);
+    assert!(world.get::<Invulnerability>(player).unwrap().is_active());
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::PendingReturn);
+}
+
+#[test]
+fn iframes_absorb_follow_up_hits_without_damage() {
+    let mut world = collision_world();
+    let first = spawn_active_enemy_bullet(&mut world, 1);
+    let second = spawn_active_enemy_bullet(&mut world, 1);
+    let player = spawn_player(&mut world, 10);
+
+    // Event order is irrelevant to the assertion: only one of the two may deal damage.
+    start(&mut world, player, first);
+    start(&mut world, second, player);
+    resolve(&mut world, process_enemy_bullet_collisions);
+
+    assert_eq!(world.get::<Health>(player).unwrap().hp, 9);
+    assert_eq!(*world.get::<BulletState>(first).unwrap(), BulletState::PendingReturn);
+    assert_eq!(*world.get::<BulletState>(second).unwrap(), BulletState::PendingReturn);
+}
+
+#[test]
+fn enemy_bullet_ignores_non_player_contacts() {
+    let mut world = collision_world();
+    let bullet = spawn_active_enemy_bullet(&mut world, 1);
+    let wall = world
+        .spawn(CollisionLayers::new(Layer::World, [Layer::EnemyBullet]))
+        .id();
+
+    start(&mut world, bullet, wall);
+    resolve(&mut world, process_enemy_bullet_collisions);
+
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::Active);
+}
+
+// -----------------------------------------------------------------------------
+// Weapon definitions
+// -----------------------------------------------------------------------------
+
+#[test]
+fn default_weapon_matches_single_shot_pistol() {
+    let def = WeaponDef::default();
+    let reqs: Vec<_> = def
+        .bullet_requests(BulletKind::Player, Vec2::ZERO, Vec2::X, None)
+        .collect();
+
+    assert_eq!(reqs.len(), 1);
+    assert_eq!(reqs[0].pos, Vec2::new(def.muzzle_offset, 0.0));
+    assert_eq!(reqs[0].vel, Vec2::new(def.speed, 0.0));
+    assert_eq!(reqs[0].damage, 1);
+}
+
+#[test]
+fn weapon_def_parses_partial_ron_with_defaults() {
+    let def = WeaponDef::from_ron_str(r#"(name: "Shotgun", pellets: 5, spread_deg: 40.0)"#)
+        .expect("valid weapon RON");
+
+    assert_eq!(def.name, "Shotgun");
+    assert_eq!(def.pellets, 5);
+    assert_eq!(def.damage, WeaponDef::default().damage);
+}
+
+#[test]
+fn bundled_weapon_files_parse() {
+    for src in [
+        include_str!("../../../assets/weapons/pistol.weapon.ron"),
+        include_str!("../../../assets/weapons/rifle.weapon.ron"),
+        include_str!("../../../assets/weapons/smg.weapon.ron"),
+        include_str!("../../../assets/weapons/shotgun.weapon.ron"),
+        include_str!("../../../assets/weapons/launcher.weapon.ron"),
+        include_str!("../../../assets/weapons/railgun.weapon.ron"),
+        include_str!("../../../assets/weapons/beam.weapon.ron"),
+        include_str!("../../../assets/weapons/charger.weapon.ron"),
+    ] {
+        WeaponDef::from_ron_str(src).expect("bundled weapon must parse");
+    }
+}
+
+#[test]
+fn pellets_fan_symmetrically_across_spread() {
+    let def = WeaponDef { pellets: 5, spread_deg: 40.0, ..default() };
+    let reqs: Vec<_> = def
+        .bullet_requests(BulletKind::Player, Vec2::ZERO, Vec2::Y, None)
+        .collect();
+
+    assert_eq!(reqs.len(), 5);
+
+    let angles: Vec<f32> = reqs.iter().map(|r| Vec2::Y.angle_to(r.vel).to_degrees()).collect();
+    assert!((angles[0] + 20.0).abs() < 1e-3);
+    assert!(angles[2].abs() < 1e-3);
+    assert!((angles[4] - 20.0).abs() < 1e-3);
+
+    for r in &reqs {
+        assert!((r.vel.length() - def.speed).abs() < 1e-2);
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Fire cadence + ammo
+// -----------------------------------------------------------------------------
+
+#[test]
+fn fire_rate_cooldown_gates_follow_up_shots() {
+    let def = WeaponDef { fire_rate: 10.0, ..default() };
+    let mut state = WeaponState::full(&def);
+
+    assert!(state.try_fire(&def));
+    assert!(!state.try_fire(&def), "cooldown should block an immediate second shot");
+
+    // Zero virtual dt (hitstop) must not advance the cooldown.
+    state.tick(&def, 0.0);
+    assert!(!state.try_fire(&def));
+
+    state.tick(&def, 0.1);
+    assert!(state.try_fire(&def));
+    assert_eq!(state.magazine, def.magazine_size - 2);
+}
+
+#[test]
+fn empty_magazine_triggers_reload_that_draws_from_reserve() {
+    let def = WeaponDef {
+        fire_rate: 1000.0,
+        magazine_size: 2,
+        reserve_ammo: 3,
+        reload_secs: 0.5,
+        ..default()
+    };
+    let mut state = WeaponState::full(&def);
+
+    for _ in 0..2 {
+        state.tick(&def, 0.01);
+        assert!(state.try_fire(&def));
+    }
+
+    state.tick(&def, 0.01);
+    assert!(!state.try_fire(&def), "empty magazine cannot fire");
+    assert!(state.is_reloading());
+
+    state.tick(&def, 0.25);
+    assert!(state.is_reloading());
+    state.tick(&def, 0.25);
+
+    assert_eq!(state.reload, ReloadState::Ready);
+    assert_eq!(state.magazine, 2);
+    assert_eq!(state.reserve, 1);
+}
+
+#[test]
+fn reload_is_refused_when_full_or_out_of_reserve() {
+    let def = WeaponDef { magazine_size: 4, reserve_ammo: 0, ..default() };
+    let mut state = WeaponState::full(&def);
+    assert!(!state.start_reload(&def), "full magazine");
+
+    state.magazine = 1;
+    assert!(!state.start_reload(&def), "no reserve");
+}
+
+#[test]
+fn cycling_weapons_keeps_each_slots_ammo() {
+    let def = WeaponDef { magazine_size: 4, reserve_ammo: 8, ..default() };
+    let mut loadout = WeaponLoadout { weapons: vec![Handle::default(); 2], ..default() };
+
+    // First draw of a slot is full.
+    let mut pistol = loadout.swap_state(None, WeaponState::default(), &def);
+    assert_eq!(pistol, WeaponState::full(&def));
+    assert!(pistol.try_fire(&def));
+    pistol.charge_secs = 0.3;
+
+    loadout.cycle();
+    let other = loadout.swap_state(Some(0), pistol.clone(), &def);
+    assert_eq!(other, WeaponState::full(&def));
+
+    // Back to slot 0: the spent round stays spent, only the charge is dropped.
+    loadout.cycle();
+    let back = loadout.swap_state(Some(1), other, &def);
+    assert_eq!(back, WeaponState { charge_secs: 0.0, ..pistol });
+    assert_eq!(back.magazine, 3);
+}
+
+// -----------------------------------------------------------------------------
+// Pools + overflow policies
+// -----------------------------------------------------------------------------
+
+fn request(kind: BulletKind, x: f32) -> SpawnBulletRequest {
+    SpawnBulletRequest {
+        kind,
+        pos: Vec2::new(x, 0.0),
+        vel: Vec2::X,
+        damage: 1,
+        modifiers: default(),
+        lifetime: Bullet::DEFAULT_LIFETIME_SECS,
+        max_range: Bullet::DEFAULT_MAX_RANGE,
+        owner: None,
+        scale: 1.0,
+        fired_at: None,
+    }
+}
+
+/// World with a tiny enemy pool using `policy` and an empty player pool.
+fn pool_world(capacity: usize, policy: OverflowPolicy) -> World {
+    let mut world = World::new();
+    world.init_resource::<Messages<SpawnBulletRequest>>();
+    world.insert_resource(Time::<Fixed>::default());
+    world.insert_resource(BulletPools {
+        player: BulletPool::new(BulletKind::Player, 0, OverflowPolicy::DropNewest),
+        enemy: BulletPool::new(BulletKind::Enemy, capacity, policy),
+        next_activation: 0,
+    });
+    run_system_once(&mut world, init_bullet_pool);
+    world
+}
+
+fn fire(world: &mut World, reqs: impl IntoIterator<Item = SpawnBulletRequest>) {
+    world.resource_mut::<Messages<SpawnBulletRequest>>().write_batch(reqs);
+    run_system_once(world, allocate_bullets_from_pool);
+    world.resource_mut::<Messages<SpawnBulletRequest>>().clear();
+}
+
+fn active_xs(world: &mut World) -> Vec<f32> {
+    let mut xs: Vec<f32> = world
+        .query::<(&BulletState, &Transform)>()
+        .iter(world)
+        .filter(|(s, _)| **s == BulletState::Active)
+        .map(|(_, tf)| tf.translation.x)
+        .collect();
+    xs.sort_by(f32::total_cmp);
+    xs
+}
+
+#[test]
+fn drop_newest_discards_requests_beyond_capacity() {
+    let mut world = pool_world(2, OverflowPolicy::DropNewest);
+    fire(&mut world, (0..3).map(|i| request(BulletKind::Enemy, i as f32)));
+
+    assert_eq!(active_xs(&mut world), vec![0.0, 1.0]);
+}
+
+#[test]
+fn recycle_oldest_refires_the_earliest_active_bullet() {
+    let mut world = pool_world(2, OverflowPolicy::RecycleOldest);
+    fire(&mut world, [request(BulletKind::Enemy, 0.0), request(BulletKind::Enemy, 1.0)]);
+    fire(&mut world, [request(BulletKind::Enemy, 2.0)]);
+
+    assert_eq!(active_xs(&mut world), vec![1.0, 2.0]);
+}
+
+#[test]
+fn grow_spawns_chunks_up_to_the_ceiling() {
+    let mut world = pool_world(1, OverflowPolicy::Grow { chunk: 2, max_capacity: 3 });
+    fire(&mut world, (0..5).map(|i| request(BulletKind::Enemy, i as f32)));
+
+    // 1 pre-spawned + 2 grown = ceiling of 3; the rest are dropped.
+    assert_eq!(active_xs(&mut world), vec![0.0, 1.0, 2.0]);
+    let pools = world.resource::<BulletPools>();
+    assert_eq!(pools.enemy.capacity, 3);
+    assert!(pools.enemy.free.is_empty());
+}
+
+#[test]
+fn pools_are_isolated_per_kind() {
+    let mut world = pool_world(1, OverflowPolicy::DropNewest);
+    // The player pool is empty, so player requests cannot steal enemy bullets.
+    fire(&mut world, [request(BulletKind::Player, 5.0), request(BulletKind::Enemy, 7.0)]);
+
+    assert_eq!(active_xs(&mut world), vec![7.0]);
+}
+
+// -----------------------------------------------------------------------------
+// Lifetime + arena escape
+// -----------------------------------------------------------------------------
+
+fn expiry_world(dt: f32) -> World {
+    let mut world = World::new();
+    world.insert_resource(fixed_time_with_delta(dt));
+    world.insert_resource(crate::plugins::world::ArenaBounds::default());
+    world
+}
+
+fn spawn_flying(world: &mut World, pos: Vec2, vel: Vec2, life: f32, range: f32) -> Entity {
+    world
+        .spawn((
+            PooledBullet,
+            BulletState::Active,
+            Bullet {
+                life_remaining: life,
+                range_remaining: range,
+                ..Bullet::pooled(BulletKind::Player)
+            },
+            Position(pos),
+            LinearVelocity(vel),
+        ))
+        .id()
+}
+
+#[test]
+fn bullets_expire_by_lifetime_range_or_leaving_the_arena() {
+    let mut world = expiry_world(0.1);
+    let alive = spawn_flying(&mut world, Vec2::ZERO, Vec2::X * 100.0, 1.0, 1000.0);
+    let old = spawn_flying(&mut world, Vec2::ZERO, Vec2::X * 100.0, 0.05, 1000.0);
+    let far = spawn_flying(&mut world, Vec2::ZERO, Vec2::X * 100.0, 1.0, 5.0);
+    let escaped = spawn_flying(&mut world, Vec2::new(1.0e5, 0.0), Vec2::ZERO, 1.0, 1000.0);
+
+    run_system_once(&mut world, expire_bullets);
+
+    assert_eq!(*world.get::<BulletState>(alive).unwrap(), BulletState::Active);
+    assert_eq!(*world.get::<BulletState>(old).unwrap(), BulletState::PendingReturn);
+    assert_eq!(*world.get::<BulletState>(far).unwrap(), BulletState::PendingReturn);
+    assert_eq!(*world.get::<BulletState>(escaped).unwrap(), BulletState::PendingReturn);
+
+    let bullet = world.get::<Bullet>(alive).unwrap();
+    assert!((bullet.life_remaining - 0.9).abs() < 1e-4);
+    assert!((bullet.range_remaining - 990.0).abs() < 1e-2);
+}
+
+// -----------------------------------------------------------------------------
+// Pierce / armour-piercing / ricochet
+// -----------------------------------------------------------------------------
+
+fn spawn_player_bullet(world: &mut World, modifiers: BulletModifiers, pos: Vec2, vel: Vec2) -> Entity {
+    let req = SpawnBulletRequest { modifiers, vel, pos, ..request(BulletKind::Player, 0.0) };
+    let mut bullet = Bullet::pooled(BulletKind::Player);
+    bullet.reset_for_fire(&req, 1);
+
+    world
+        .spawn((
+            PooledBullet,
+            BulletState::Active,
+            bullet,
+            CollisionStamp::default(),
+            Position(pos),
+            LinearVelocity(vel),
+            super::pool::active_player_layers(),
+        ))
+        .id()
+}
+
+fn spawn_enemy(world: &mut World, armour: u16, hp: i32) -> Entity {
+    world
+        .spawn((
+            Enemy,
+            Armour { hits_remaining: armour, max_hits: armour },
+            Health { hp },
+            Knockback::default(),
+            Weight(2.0),
+            CollisionLayers::new(Layer::Enemy, [Layer::PlayerBullet]),
+        ))
+        .id()
+}
+
+#[test]
+fn armour_blocks_damage_and_consumes_bullet() {
+    let mut world = collision_world();
+    let bullet = spawn_player_bullet(&mut world, default(), Vec2::ZERO, Vec2::X);
+    let enemy = spawn_enemy(&mut world, 2, 5);
+
+    start(&mut world, bullet, enemy);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    assert_eq!(world.get::<Armour>(enemy).unwrap().hits_remaining, 1);
+    assert_eq!(world.get::<Health>(enemy).unwrap().hp, 5);
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::PendingReturn);
+}
+
+#[test]
+fn armour_piercing_bypasses_armour() {
+    let mut world = collision_world();
+    let mods = BulletModifiers { armour_piercing: true, ..default() };
+    let bullet = spawn_player_bullet(&mut world, mods, Vec2::ZERO, Vec2::X);
+    let enemy = spawn_enemy(&mut world, 2, 5);
+
+    start(&mut world, bullet, enemy);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    assert_eq!(world.get::<Armour>(enemy).unwrap().hits_remaining, 2);
+    assert_eq!(world.get::<Health>(enemy).unwrap().hp, 4);
+}
+
+#[test]
+fn bullet_status_is_applied_on_unblocked_hit() {
+    let mut world = collision_world();
+    let burn = StatusApply { kind: StatusKind::Burn, duration_secs: 2.0, potency: 1.0 };
+    let mods = BulletModifiers { armour_piercing: true, status: Some(burn), ..default() };
+    let bullet = spawn_player_bullet(&mut world, mods, Vec2::ZERO, Vec2::X);
+    let enemy = spawn_enemy(&mut world, 2, 5);
+    world.entity_mut(enemy).insert(StatusEffects::default());
+
+    start(&mut world, bullet, enemy);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    let status = world.get::<StatusEffects>(enemy).unwrap();
+    assert!(status.has(StatusKind::Burn));
+    assert_eq!(status.burn.remaining, 2.0);
+}
+
+#[test]
+fn pierce_passes_through_enemies_once_each() {
+    let mut world = collision_world();
+    let mods = BulletModifiers { pierce: 1, ..default() };
+    let bullet = spawn_player_bullet(&mut world, mods, Vec2::ZERO, Vec2::X);
+    let a = spawn_enemy(&mut world, 0, 5);
+    let b = spawn_enemy(&mut world, 0, 5);
+
+    // Same tick: first enemy reported twice (jittery contact), then a second enemy.
+    start(&mut world, bullet, a);
+    start(&mut world, a, bullet);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    assert_eq!(world.get::<Health>(a).unwrap().hp, 4);
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::Active);
+
+    world.resource_mut::<Messages<CollisionStart>>().clear();
+    start(&mut world, bullet, b);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    assert_eq!(world.get::<Health>(b).unwrap().hp, 4);
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::PendingReturn);
+    // One shot, one hit for accuracy, however many enemies it passed through.
+    assert_eq!(world.resource::<RunStats>().hits, 1);
+}
+
+#[test]
+fn pierce_never_rehits_an_enemy_it_came_back_to() {
+    let mut world = collision_world();
+    let mods = BulletModifiers { pierce: 2, ..default() };
+    let bullet = spawn_player_bullet(&mut world, mods, Vec2::ZERO, Vec2::X);
+    let a = spawn_enemy(&mut world, 0, 5);
+    let b = spawn_enemy(&mut world, 0, 5);
+
+    // A -> B -> A over three ticks (e.g. homing or a ricochet bringing it back).
+    for target in [a, b, a] {
+        world.resource_mut::<Messages<CollisionStart>>().clear();
+        start(&mut world, bullet, target);
+        resolve(&mut world, process_player_bullet_collisions);
+    }
+
+    assert_eq!(world.get::<Health>(a).unwrap().hp, 4);
+    assert_eq!(world.get::<Health>(b).unwrap().hp, 4);
+    assert_eq!(world.get::<Bullet>(bullet).unwrap().pierce_left, 0);
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::Active);
+}
+
+#[test]
+fn ricochet_reflects_off_wall_face_and_spends_budget() {
+    let mut world = collision_world();
+    let mods = BulletModifiers {
+        ricochet: Ricochet { bounces: 1, speed_retain: 0.5 },
+        ..default()
+    };
+    // Moving right at 100 px/s; dt = 0.1 so it came from x = 90 and is now inside the wall.
+    let bullet = spawn_player_bullet(&mut world, mods, Vec2::new(100.0, 0.0), Vec2::new(100.0, 0.0));
+    let wall = world
+        .spawn((
+            CollisionLayers::new(Layer::World, [Layer::PlayerBullet]),
+            ColliderAabb::from_min_max(Vec2::new(98.0, -50.0), Vec2::new(130.0, 50.0)),
+        ))
+        .id();
+
+    start(&mut world, bullet, wall);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    let vel = world.get::<LinearVelocity>(bullet).unwrap().0;
+    assert!((vel - Vec2::new(-50.0, 0.0)).length() < 1e-3);
+    let pos = world.get::<Position>(bullet).unwrap().0;
+    assert!((pos.x - (98.0 - Bullet::RADIUS)).abs() < 1e-3);
+    assert_eq!(world.get::<Bullet>(bullet).unwrap().wall_bounces_left, 0);
+
+    // Budget spent: the next wall hit returns the bullet.
+    world.resource_mut::<Messages<CollisionStart>>().clear();
+    start(&mut world, bullet, wall);
+    resolve(&mut world, process_player_bullet_collisions);
+    assert_eq!(*world.get::<BulletState>(bullet).unwrap(), BulletState::PendingReturn);
+}
+
+#[test]
+fn default_ricochet_returns_on_the_third_wall_contact() {
+    let mut world = collision_world();
+    let bullet = spawn_player_bullet(&mut world, default(), Vec2::new(100.0, 0.0), Vec2::new(100.0, 0.0));
+    let wall = world
+        .spawn((
+            CollisionLayers::new(Layer::World, [Layer::PlayerBullet]),
+            ColliderAabb::from_min_max(Vec2::new(98.0, -50.0), Vec2::new(130.0, 50.0)),
+        ))
+        .id();
+
+    for contact in 1..=3 {
+        world.resource_mut::<Messages<CollisionStart>>().clear();
+        start(&mut world, bullet, wall);
+        resolve(&mut world, process_player_bullet_collisions);
+
+        let expected = if contact < 3 { BulletState::Active } else { BulletState::PendingReturn };
+        assert_eq!(*world.get::<BulletState>(bullet).unwrap(), expected, "contact {contact}");
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Homing
+// -----------------------------------------------------------------------------
+
+fn homing_world() -> World {
+    let mut world = collision_world();
+    let player = world.spawn((Player, Position(Vec2::new(0.0, -500.0)))).id();
+    world.insert_resource(PlayerEntity(Some(player)));
+    world
+}
+
+fn homing_mods() -> BulletModifiers {
+    BulletModifiers {
+        homing: Some(Homing { turn_rate_deg: 90.0, cone_deg: 90.0, range: 500.0 }),
+        ..default()
+    }
+}
+
+#[test]
+fn acquire_picks_nearest_target_inside_cone_and_range() {
+    let homing = Homing { turn_rate_deg: 90.0, cone_deg: 90.0, range: 300.0 };
+    let candidates = [
+        Vec2::new(-50.0, 0.0),   // behind
+        Vec2::new(200.0, 100.0), // in cone, further
+        Vec2::new(100.0, 60.0),  // in cone, nearest
+        Vec2::new(400.0, 0.0),   // out of range
+        Vec2::new(10.0, 100.0),  // outside the 45° half-cone
+    ];
+
+    let to = acquire(&homing, Vec2::ZERO, Vec2::X, candidates);
+
+    assert_eq!(to, Some(Vec2::new(100.0, 60.0)));
+    assert_eq!(acquire(&homing, Vec2::ZERO, Vec2::NEG_X, [Vec2::new(100.0, 0.0)]), None);
+}
+
+#[test]
+fn steer_is_turn_rate_limited_and_keeps_speed() {
+    let vel = Vec2::new(100.0, 0.0);
+
+    let limited = steer(vel, Vec2::Y, 0.1);
+    assert!((limited.length() - 100.0).abs() < 1e-3);
+    assert!((Vec2::X.angle_to(limited) - 0.1).abs() < 1e-5);
+
+    let snapped = steer(vel, Vec2::new(1.0, 0.01), 0.1);
+    assert!((snapped.normalize() - Vec2::new(1.0, 0.01).normalize()).length() < 1e-5);
+}
+
+#[test]
+fn homing_player_bullet_turns_toward_live_enemy_only() {
+    let mut world = homing_world();
+    let homing = spawn_player_bullet(&mut world, homing_mods(), Vec2::ZERO, Vec2::new(100.0, 0.0));
+    let straight = spawn_player_bullet(&mut world, default(), Vec2::ZERO, Vec2::new(100.0, 0.0));
+    world.spawn((
+        Enemy,
+        Position(Vec2::new(300.0, 200.0)),
+        CollisionLayers::new(Layer::Enemy, [Layer::PlayerBullet]),
+    ));
+    // Dying enemies clear their filters and must not attract bullets.
+    world.spawn((
+        Enemy,
+        Position(Vec2::new(100.0, -50.0)),
+        CollisionLayers::new(Layer::Enemy, [] as [Layer; 0]),
+    ));
+
+    run_system_once(&mut world, steer_homing_bullets);
+
+    // dt = 0.1 s at 90°/s: turned 9° toward +y.
+    let vel = world.get::<LinearVelocity>(homing).unwrap().0;
+    assert!((Vec2::X.angle_to(vel) - 9f32.to_radians()).abs() < 1e-4);
+    assert_eq!(world.get::<LinearVelocity>(straight).unwrap().0, Vec2::new(100.0, 0.0));
+}
+
+#[test]
+fn homing_enemy_bullet_turns_toward_player() {
+    let mut world = homing_world();
+    let req = SpawnBulletRequest {
+        modifiers: homing_mods(),
+        vel: Vec2::new(100.0, 0.0),
+        ..request(BulletKind::Enemy, 0.0)
+    };
+    let mut bullet = Bullet::pooled(BulletKind::Enemy);
+    bullet.reset_for_fire(&req, 1);
+    // Player sits at (0, -500); start at (-300, -300) heading +x.
+    let e = world
+        .spawn((
+            PooledBullet,
+            BulletState::Active,
+            bullet,
+            Position(Vec2::new(-300.0, -300.0)),
+            LinearVelocity(req.vel),
+        ))
+        .id();
+
+    run_system_once(&mut world, steer_homing_bullets);
+
+    let vel = world.get::<LinearVelocity>(e).unwrap().0;
+    assert!(vel.y < 0.0, "expected a turn toward the player, got {vel:?}");
+}
+
+// -----------------------------------------------------------------------------
+// Emitters + bullet patterns
+// -----------------------------------------------------------------------------
+
+fn angles_deg(dirs: &[Vec2]) -> Vec<f32> {
+    dirs.iter().map(|d| d.to_angle().to_degrees()).collect()
+}
+
+#[test]
+fn bundled_pattern_files_parse() {
+    for src in [
+        include_str!("../../../assets/patterns/ring.pattern.ron"),
+        include_str!("../../../assets/patterns/spiral.pattern.ron"),
+        include_str!("../../../assets/patterns/fan.pattern.ron"),
+        include_str!("../../../assets/patterns/aimed_burst.pattern.ron"),
+        include_str!("../../../assets/patterns/wave.pattern.ron"),
+        include_str!("../../../assets/patterns/orbs.pattern.ron"),
+    ] {
+        BulletPattern::from_ron_str(src).expect("bundled pattern must parse");
+    }
+}
+
+#[test]
+fn ring_and_fan_volleys_are_evenly_spaced() {
+    let ring = BulletPattern { shape: PatternShape::Ring { count: 4 }, ..default() };
+    let dirs = ring.volley(0.0, 0.0);
+    assert_eq!(dirs.len(), 4);
+    for (got, want) in dirs.iter().zip([Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y]) {
+        assert!((*got - want).length() < 1e-4, "{dirs:?}");
+    }
+
+    let fan = BulletPattern { shape: PatternShape::Fan { count: 3, spread_deg: 40.0 }, ..default() };
+    let a = angles_deg(&fan.volley(90f32.to_radians(), 0.0));
+    for (got, want) in a.iter().zip([70.0, 90.0, 110.0]) {
+        assert!((got - want).abs() < 1e-3, "{a:?}");
+    }
+}
+
+#[test]
+fn wave_sways_around_base_angle() {
+    let wave = BulletPattern {
+        shape: PatternShape::Wave { count: 1, spread_deg: 0.0, amplitude_deg: 30.0, frequency_hz: 1.0 },
+        ..default()
+    };
+
+    assert!(angles_deg(&wave.volley(0.0, 0.0))[0].abs() < 1e-3);
+    assert!((angles_deg(&wave.volley(0.0, 0.25))[0] - 30.0).abs() < 1e-3);
+    assert!((angles_deg(&wave.volley(0.0, 0.75))[0] + 30.0).abs() < 1e-3);
+}
+
+#[test]
+fn spiral_advances_step_per_volley_and_spin_per_second() {
+    let spiral = BulletPattern {
+        shape: PatternShape::Spiral { arms: 2, step_deg: 10.0 },
+        period_secs: 0.1,
+        spin_deg_per_sec: 100.0,
+        ..default()
+    };
+    let mut emitter = Emitter::new(Handle::default());
+    let mut bases = Vec::new();
+
+    // dt = 0.25 s: volleys due at t = 0.0, 0.1, 0.2 of the cooldown timeline.
+    let mut due = Vec::new();
+    emitter.step(&spiral, 0.25, |base, elapsed, late| {
+        bases.push(base.to_degrees());
+        due.push((elapsed, late));
+    });
+
+    assert_eq!(bases.len(), 3);
+    // Each volley gets the spin up to its own due time (10° per 0.1 s) plus +10° per
+    // earlier volley, not the end-of-step phase.
+    for (got, want) in bases.iter().zip([0.0, 20.0, 40.0]) {
+        assert!((got - want).abs() < 1e-3, "{bases:?}");
+    }
+    for ((elapsed, late), want) in due.iter().zip([0.0, 0.1, 0.2]) {
+        assert!((elapsed - want).abs() < 1e-5 && (elapsed + late - 0.25).abs() < 1e-5, "{due:?}");
+    }
+    assert!((emitter.angle.to_degrees() - 55.0).abs() < 1e-3);
+}
+
+#[test]
+fn aimed_burst_fires_shots_then_waits_period() {
+    let burst = BulletPattern {
+        shape: PatternShape::AimedBurst { shots: 3, pellets: 1, spread_deg: 0.0, shot_interval_secs: 0.125 },
+        period_secs: 1.0,
+        ..default()
+    };
+    let mut emitter = Emitter::new(Handle::default());
+    let mut fired = Vec::new();
+
+    // Binary-exact dt so the cooldown timeline has no rounding.
+    for tick in 0..30 {
+        emitter.step(&burst, 0.0625, |_, _, _| fired.push(tick));
+    }
+
+    // Shots due at t = 0, 0.125, 0.25 fire on the first tick at or after them
+    // (tick n ends at t = 0.0625 * (n + 1)); the next burst is due 1 s after the third.
+    assert_eq!(fired, vec![0, 1, 3, 19, 21, 23]);
+}
+
+fn emitter_world(pattern: BulletPattern) -> (World, Handle<BulletPattern>) {
+    let mut world = World::new();
+    world.insert_resource(fixed_time_with_delta(0.05));
+    world.init_resource::<Messages<SpawnBulletRequest>>();
+    world.init_resource::<Assets<BulletPattern>>();
+
+    let player = world.spawn((Player, Transform::from_xyz(0.0, -100.0, 0.0))).id();
+    world.insert_resource(PlayerEntity(Some(player)));
+
+    let handle = world.resource_mut::<Assets<BulletPattern>>().add(pattern);
+    (world, handle)
+}
+
+fn written(world: &World) -> Vec<SpawnBulletRequest> {
+    world
+        .resource::<Messages<SpawnBulletRequest>>()
+        .iter_current_update_messages()
+        .copied()
+        .collect()
+}
+
+#[test]
+fn aimed_emitter_writes_enemy_requests_toward_player() {
+    let pattern = BulletPattern {
+        shape: PatternShape::Fan { count: 3, spread_deg: 20.0 },
+        aimed: true,
+        speed: 200.0,
+        damage: 2,
+        ..default()
+    };
+    let (mut world, handle) = emitter_world(pattern.clone());
+    let emitter = world.spawn((Transform::from_xyz(0.0, 100.0, 0.0), Emitter::new(handle))).id();
+
+    run_system_once(&mut world, step_emitters);
+
+    let reqs = written(&world);
+    assert_eq!(reqs.len(), 3);
+    for r in &reqs {
+        assert_eq!(r.kind, BulletKind::Enemy);
+        assert_eq!(r.owner, Some(emitter));
+        assert_eq!(r.damage, 2);
+        assert_eq!(r.modifiers.ricochet.bounces, 0);
+        assert!((r.vel.length() - 200.0).abs() < 1e-2);
+    }
+    // Middle pellet points straight down at the player.
+    assert!((reqs[1].vel.normalize() - Vec2::NEG_Y).length() < 1e-4);
+    assert!((reqs[1].pos - Vec2::new(0.0, 100.0 - pattern.muzzle_offset)).length() < 1e-3);
+    // Due at the start of the step (t = 0), which ended at t = 0.05.
+    assert_eq!(reqs[1].fired_at, Some(0.0));
+}
+
+#[test]
+fn late_emitter_bullets_catch_up_to_their_due_time() {
+    let mut world = pool_world(2, OverflowPolicy::DropNewest);
+    world.resource_mut::<Time<Fixed>>().advance_by(std::time::Duration::from_secs_f32(0.5));
+
+    // Due 0.25 s before the allocator runs: spawned a quarter second down its path.
+    let late = SpawnBulletRequest {
+        vel: Vec2::new(100.0, 0.0),
+        fired_at: Some(0.25),
+        ..request(BulletKind::Enemy, 0.0)
+    };
+    fire(&mut world, [late, request(BulletKind::Enemy, 3.0)]);
+
+    assert_eq!(active_xs(&mut world), vec![3.0, 25.0]);
+}
+
+#[test]
+fn disabled_or_unloaded_emitters_stay_silent() {
+    let (mut world, handle) = emitter_world(BulletPattern::default());
+    world.spawn((Transform::default(), Emitter { enabled: false, ..Emitter::new(handle) }));
+    world.spawn((Transform::default(), Emitter::new(Handle::default())));
+
+    run_system_once(&mut world, step_emitters);
+
+    assert!(written(&world).is_empty());
+}
+
+// -----------------------------------------------------------------------------
+// Explosions
+// -----------------------------------------------------------------------------
+
+fn blast(edge_falloff: f32) -> Explosion {
+    Explosion {
+        kind: BulletKind::Player,
+        owner: None,
+        pos: Vec2::ZERO,
+        radius: 100.0,
+        damage: 4,
+        damage_type: DamageType::Explosive,
+        edge_falloff,
+        impulse: 400.0,
+        armour_piercing: false,
+    }
+}
+
+#[test]
+fn explosion_damage_and_impulse_fall_off_toward_rim() {
+    let ex = blast(0.25);
+
+    assert_eq!(ex.damage_at(0.0), 4);
+    assert_eq!(ex.damage_at(50.0), 3); // 4 * 0.625 = 2.5 -> 3
+    assert_eq!(ex.damage_at(100.0), 1);
+    assert_eq!(ex.damage_at(500.0), 1, "clamped to the rim");
+
+    let near = ex.impulse_at(Vec2::new(10.0, 0.0));
+    let far = ex.impulse_at(Vec2::new(0.0, -90.0));
+    assert!(near.x > 0.0 && near.y == 0.0, "pushes away from the centre");
+    assert!(far.y < 0.0);
+    assert!(near.length() > far.length());
+    assert_eq!(ex.impulse_at(Vec2::ZERO), Vec2::ZERO);
+}
+
+fn explosion_world() -> World {
+    let mut world = World::new();
+    world.insert_resource(crate::plugins::world::ArenaBounds::default());
+    world.init_resource::<Messages<Explosion>>();
+    world
+}
+
+fn spawn_returning(world: &mut World, explosive: Explosive, pos: Vec2, expired: bool) {
+    let mods = BulletModifiers { explosive: Some(explosive), ..default() };
+    let req = SpawnBulletRequest { modifiers: mods, ..request(BulletKind::Player, 0.0) };
+    let mut bullet = Bullet::pooled(BulletKind::Player);
+    bullet.reset_for_fire(&req, 1);
+    if expired {
+        bullet.life_remaining = 0.0;
+    }
+    world.spawn((PooledBullet, BulletState::PendingReturn, bullet, Position(pos)));
+}
+
+fn explosions(world: &World) -> Vec<Explosion> {
+    world.resource::<Messages<Explosion>>().iter_current_update_messages().copied().collect()
+}
+
+#[test]
+fn returning_explosive_bullets_detonate_where_they_stopped() {
+    let mut world = explosion_world();
+    spawn_returning(&mut world, Explosive::default(), Vec2::new(30.0, 40.0), false);
+    // Plain bullets and active explosive bullets do nothing.
+    world.spawn((PooledBullet, BulletState::PendingReturn, Bullet::pooled(BulletKind::Player), Position(Vec2::ZERO)));
+    let mut flying = Bullet::pooled(BulletKind::Player);
+    flying.explosive = Some(Explosive::default());
+    world.spawn((PooledBullet, BulletState::Active, flying, Position(Vec2::ZERO)));
+
+    run_system_once(&mut world, detonate_explosives);
+
+    let out = explosions(&world);
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].pos, Vec2::new(30.0, 40.0));
+    assert_eq!(out[0].kind, BulletKind::Player);
+    assert_eq!(out[0].radius, Explosive::default().radius);
+}
+
+#[test]
+fn expiry_detonation_is_opt_in_and_escaped_bullets_fizzle() {
+    let mut world = explosion_world();
+    let fizzle = Explosive { on_expiry: false, ..default() };
+    spawn_returning(&mut world, fizzle, Vec2::ZERO, true);
+    spawn_returning(&mut world, Explosive::default(), Vec2::new(1.0, 0.0), true);
+    spawn_returning(&mut world, Explosive::default(), Vec2::new(100_000.0, 0.0), false);
+
+    run_system_once(&mut world, detonate_explosives);
+
+    let out = explosions(&world);
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].pos, Vec2::new(1.0, 0.0));
+}
+
+// -----------------------------------------------------------------------------
+// Knockback
+// -----------------------------------------------------------------------------
+
+#[test]
+fn knockback_scales_by_weight_and_opens_stun_window() {
+    let mut kb = Knockback::default();
+    kb.apply(Vec2::ZERO, Weight(1.0));
+    assert!(!kb.is_stunned(), "zero impulse is not a hit");
+
+    kb.apply(Vec2::new(200.0, 0.0), Weight(4.0));
+    assert_eq!(kb.velocity, Vec2::new(50.0, 0.0));
+    assert!(kb.is_stunned());
+    assert_eq!(kb.blend(Vec2::new(0.0, 100.0)), Vec2::new(50.0, 0.0));
+
+    kb.tick(Knockback::STUN_SECS);
+    assert!(!kb.is_stunned());
+    assert!(kb.velocity.x < 50.0);
+}
+
+#[test]
+fn bullet_hits_shove_enemy_along_flight_direction() {
+    let mut world = collision_world();
+    // Armour up: the hit is blocked but still shoves.
+    let bullet = spawn_player_bullet(&mut world, default(), Vec2::ZERO, Vec2::new(0.0, 500.0));
+    let enemy = spawn_enemy(&mut world, 1, 5);
+
+    start(&mut world, bullet, enemy);
+    resolve(&mut world, process_player_bullet_collisions);
+
+    let kb = world.get::<Knockback>(enemy).unwrap();
+    let expected = BulletModifiers::default().knockback / 2.0;
+    assert!((kb.velocity - Vec2::new(0.0, expected)).length() < 1e-3);
+    assert!(kb.is_stunned());
+}
+
+#[test]
+fn enemy_bullet_shoves_player_only_outside_iframes() {
+    let mut world = collision_world();
+    let bullet = spawn_active_enemy_bullet(&mut world, 1);
+    let player = spawn_player(&mut world, 10);
+    world.get_mut::<Invulnerability>(player).unwrap().grant(1.0);
+
+    start(&mut world, bullet, player);
+    resolve(&mut world, process_enemy_bullet_collisions);
+    assert_eq!(*world.get::<Knockback>(player).unwrap(), Knockback::default());
+
+    world.get_mut::<Invulnerability>(player).unwrap().remaining = 0.0;
+    let bullet = spawn_active_enemy_bullet(&mut world, 1);
+    world.resource_mut::<Messages<CollisionStart>>().clear();
+    start(&mut world, bullet, player);
+    resolve(&mut world, process_enemy_bullet_collisions);
+
+    assert_eq!(world.get::<Knockback>(player).unwrap().velocity, Vec2::new(0.0, -60.0));
+}
+
+#[test]
+fn explosion_damage_request_carries_falloff_impulse_and_type() {
+    let mut world = World::new();
+    let target = world.spawn_empty().id();
+    let ex = Explosion { armour_piercing: true, ..blast(0.25) };
+
+    let req = ex.damage_request(target, Vec2::new(50.0, 0.0));
+
+    assert_eq!(req.target, target);
+    assert_eq!(req.amount, 3);
+    assert_eq!(req.damage_type, DamageType::Explosive);
+    assert_eq!(req.position, ex.pos);
+    assert!(req.impulse.x > 0.0);
+    assert!(req.armour_piercing);
+}
+
+#[test]
+fn bullet_hits_become_damage_requests_with_owner_as_source() {
+    let mut world = collision_world();
+    let shooter = world.spawn_empty().id();
+    let req = SpawnBulletRequest { owner: Some(shooter), vel: Vec2::Y, ..request(BulletKind::Player, 0.0) };
+    let mut bullet = Bullet::pooled(BulletKind::Player);
+    bullet.reset_for_fire(&req, 1);
+    let b = world
+        .spawn((
+            PooledBullet,
+            BulletState::Active,
+            bullet,
+            CollisionStamp::default(),
+            Position(Vec2::new(3.0, 4.0)),
+            LinearVelocity(Vec2::new(0.0, 10.0)),
+        ))
+        .id();
+    let enemy = spawn_enemy(&mut world, 0, 5);
+
+    start(&mut world, b, enemy);
+    run_system_once(&mut world, process_player_bullet_collisions);
+
+    let reqs: Vec<DamageRequest> = world
+        .resource::<Messages<DamageRequest>>()
+        .iter_current_update_messages()
+        .copied()
+        .collect();
+    assert_eq!(reqs.len(), 1);
+    assert_eq!(reqs[0].source, Some(shooter));
+    assert_eq!(reqs[0].target, enemy);
+    assert_eq!(reqs[0].position, Vec2::new(3.0, 4.0));
+    assert_eq!(reqs[0].damage_type, DamageType::Kinetic);
+    // Nothing is applied until the damage pipeline runs.
+    assert_eq!(world.get::<Health>(enemy).unwrap().hp, 5);
+}
+
+// -----------------------------------------------------------------------------
+// Hitscan + beams
+// -----------------------------------------------------------------------------
+
+/// Axis-aligned box standing in for a collider in `trace` tests.
+struct TestBox {
+    entity: Entity,
+    min: Vec2,
+    max: Vec2,
+    world: bool,
+}
+
+/// Slab test: entry distance + entry face normal of a ray against a box.
+fn ray_vs_box(b: &TestBox, from: Vec2, dir: Vec2, max: f32) -> Option<RayContact> {
+    let (mut t_near, mut t_far, mut normal) = (0.0_f32, max, Vec2::ZERO);
+    for axis in 0..2 {
+        let (o, d, lo, hi) = (from[axis], dir[axis], b.min[axis], b.max[axis]);
+        if d.abs() < 1e-6 {
+            if o < lo || o > hi { return None; }
+            continue;
+        }
+        let (t0, t1) = ((lo - o) / d, (hi - o) / d);
+        let (tn, tf, n) = if t0 < t1 { (t0, t1, -1.0) } else { (t1, t0, 1.0) };
+        if tn > t_near {
+            t_near = tn;
+            normal = Vec2::ZERO;
+            normal[axis] = n;
+        }
+        t_far = t_far.min(tf);
+        if t_near > t_far { return None; }
+    }
+    Some(RayContact { entity: b.entity, distance: t_near, normal, world: b.world })
+}
+
+fn box_caster(boxes: &[TestBox]) -> impl FnMut(Vec2, Vec2, f32, &[Entity]) -> Option<RayContact> + '_ {
+    move |from, dir, max, already_hit| {
+        boxes
+            .iter()
+            .filter(|b| !already_hit.contains(&b.entity))
+            .filter_map(|b| ray_vs_box(b, from, dir, max))
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+}
+
+/// Three 20px enemies in a row along +x at 100, 200, 300, and a wall at x = 400.
+fn ray_lane(world: &mut World) -> Vec<TestBox> {
+    let mut boxes: Vec<TestBox> = [100.0, 200.0, 300.0]
+        .into_iter()
+        .map(|x| TestBox {
+            entity: world.spawn_empty().id(),
+            min: Vec2::new(x - 10.0, -10.0),
+            max: Vec2::new(x + 10.0, 10.0),
+            world: false,
+        })
+        .collect();
+    boxes.push(TestBox {
+        entity: world.spawn_empty().id(),
+        min: Vec2::new(400.0, -500.0),
+        max: Vec2::new(430.0, 500.0),
+        world: true,
+    });
+    boxes
+}
+
+#[test]
+fn ray_stops_at_first_enemy_without_pierce() {
+    let mut world = World::new();
+    let boxes = ray_lane(&mut world);
+
+    let traced = trace(Vec2::ZERO, Vec2::X, 1000.0, 0, 0, box_caster(&boxes));
+
+    assert_eq!(traced.hits.len(), 1);
+    assert_eq!(traced.hits[0].entity, boxes[0].entity);
+    assert_eq!(traced.hits[0].point, Vec2::new(90.0, 0.0));
+    assert_eq!(traced.segments, vec![(Vec2::ZERO, Vec2::new(90.0, 0.0))]);
+}
+
+#[test]
+fn ray_pierces_enemies_once_each_then_stops_at_wall() {
+    let mut world = World::new();
+    let boxes = ray_lane(&mut world);
+
+    let traced = trace(Vec2::ZERO, Vec2::X, 1000.0, 0, 5, box_caster(&boxes));
+
+    let hit: Vec<Entity> = traced.hits.iter().map(|h| h.entity).collect();
+    assert_eq!(hit, [boxes[0].entity, boxes[1].entity, boxes[2].entity]);
+    assert_eq!(traced.segments, vec![(Vec2::ZERO, Vec2::new(400.0, 0.0))]);
+
+    let limited = trace(Vec2::ZERO, Vec2::X, 1000.0, 0, 1, box_caster(&boxes));
+    assert_eq!(limited.hits.len(), 2);
+}
+
+#[test]
+fn ray_reflects_off_walls_within_budget_and_total_range() {
+    let mut world = World::new();
+    let wall = world.spawn_empty().id();
+    let enemy = world.spawn_empty().id();
+    let boxes = [
+        TestBox { entity: wall, min: Vec2::new(100.0, -500.0), max: Vec2::new(130.0, 500.0), world: true },
+        // Behind the shooter: only reachable after the bounce.
+        TestBox { entity: enemy, min: Vec2::new(-60.0, 240.0), max: Vec2::new(-40.0, 260.0), world: false },
+    ];
+    let dir = Vec2::new(1.0, 1.0).normalize();
+
+    let traced = trace(Vec2::ZERO, dir, 1000.0, 1, 0, box_caster(&boxes));
+    assert_eq!(traced.segments.len(), 2);
+    assert!((traced.segments[0].1 - Vec2::new(100.0, 100.0)).length() < 1e-3);
+    assert_eq!(traced.hits.len(), 1);
+    assert_eq!(traced.hits[0].entity, enemy);
+    assert!(traced.hits[0].dir.x < 0.0 && traced.hits[0].dir.y > 0.0);
+
+    // No bounce budget: the wall ends the ray.
+    let blocked = trace(Vec2::ZERO, dir, 1000.0, 0, 0, box_caster(&boxes));
+    assert!(blocked.hits.is_empty());
+    assert_eq!(blocked.segments.len(), 1);
+
+    // Range is shared across bounces: 60px after the wall is not enough to reach it.
+    let short = trace(Vec2::ZERO, dir, 100.0 * 2.0_f32.sqrt() + 60.0, 1, 0, box_caster(&boxes));
+    assert!(short.hits.is_empty());
+}
+
+#[test]
+fn hitscan_damage_request_carries_modifiers() {
+    let shooter = Entity::PLACEHOLDER;
+    let target = Entity::PLACEHOLDER;
+    let mods = BulletModifiers { damage_type: DamageType::Energy, armour_piercing: true, knockback: 50.0, ..default() };
+    let shot = HitscanRequest {
+        origin: Vec2::ZERO,
+        dir: Vec2::X,
+        range: 500.0,
+        bounces: 0,
+        damage: 3,
+        modifiers: mods,
+        owner: Some(shooter),
+        beam: false,
+    };
+
+    let req = shot.damage_request(target, Vec2::new(40.0, 0.0), Vec2::Y);
+    assert_eq!(req.source, Some(shooter));
+    assert_eq!((req.amount, req.damage_type), (3, DamageType::Energy));
+    assert!(req.armour_piercing && !req.periodic);
+    assert_eq!(req.impulse, Vec2::new(0.0, 50.0));
+}
+
+fn producer_world(def: WeaponDef) -> (World, Entity) {
+    let mut world = World::new();
+    world.init_resource::<Messages<SpawnBulletRequest>>();
+    world.init_resource::<Messages<HitscanRequest>>();
+    world.init_resource::<Messages<Gunshot>>();
+    world.init_resource::<RunStats>();
+    world.insert_resource(Aim { world_cursor: Some(Vec2::new(100.0, 0.0)) });
+    world.insert_resource(Time::<Virtual>::default());
+    world.insert_resource(Time::<Real>::default());
+
+    let mut buttons = ButtonInput::<MouseButton>::default();
+    buttons.press(MouseButton::Left);
+    buttons.clear();
+    world.insert_resource(buttons);
+
+    let state = WeaponState::full(&def);
+    let player = world
+        .spawn((Transform::default(), Weapon { def, source: None }, state))
+        .id();
+    world.insert_resource(PlayerEntity(Some(player)));
+    (world, player)
+}
+
+#[test]
+fn beam_fires_rays_while_held_and_spends_a_round_per_tick() {
+    let def = WeaponDef {
+        automatic: false,
+        pellets: 1,
+        delivery: Delivery::Beam { range: 300.0, bounces: 0 },
+        ..default()
+    };
+    // Held, not just pressed: a semi-automatic projectile weapon would not fire.
+    let (mut world, player) = producer_world(def);
+
+    run_system_once(&mut world, request_player_bullets);
+
+    let rays: Vec<HitscanRequest> =
+        world.resource::<Messages<HitscanRequest>>().iter_current_update_messages().copied().collect();
+    assert_eq!(rays.len(), 1);
+    assert!(rays[0].beam);
+    assert_eq!((rays[0].dir, rays[0].range), (Vec2::X, 300.0));
+    assert_eq!(rays[0].owner, Some(player));
+    assert!(world.resource::<Messages<SpawnBulletRequest>>().is_empty());
+    let shots: Vec<Gunshot> =
+        world.resource::<Messages<Gunshot>>().iter_current_update_messages().copied().collect();
+    assert_eq!(shots, vec![Gunshot { pos: Vec2::ZERO }]);
+
+    let state = world.get::<WeaponState>(player).unwrap();
+    assert_eq!(state.magazine, WeaponDef::default().magazine_size - 1);
+    assert_eq!(world.resource::<RunStats>().shots_fired, 1);
+}
+
+#[test]
+fn hitscan_pellets_fan_like_bullets() {
+    let def = WeaponDef {
+        pellets: 3,
+        spread_deg: 20.0,
+        delivery: Delivery::Hitscan { range: 800.0, bounces: 2 },
+        ..default()
+    };
+
+    let rays: Vec<_> = def.hitscan_requests(Vec2::ZERO, Vec2::Y, None).collect();
+    let bullets: Vec<_> = def.bullet_requests(BulletKind::Player, Vec2::ZERO, Vec2::Y, None).collect();
+
+    assert_eq!(rays.len(), 3);
+    for (ray, bullet) in rays.iter().zip(&bullets) {
+        assert!((ray.dir - bullet.vel.normalize()).length() < 1e-5);
+        assert_eq!((ray.range, ray.bounces, ray.beam), (800.0, 2, false));
+        assert_eq!(ray.origin, Vec2::ZERO, "rays start at the shooter, not the muzzle");
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Charge shots
+// -----------------------------------------------------------------------------
+
+fn charge() -> Charge {
+    Charge {
+        full_secs: 1.0,
+        min_level: 0.25,
+        clock: ChargeClock::Virtual,
+        damage_mult: 3.0,
+        size_mult: 2.0,
+        speed_mult: 1.5,
+        bonus_pierce: 2,
+    }
+}
+
+#[test]
+fn charge_scales_damage_size_speed_and_pierce_by_level() {
+    let c = charge();
+    let req = request(BulletKind::Player, 0.0);
+
+    let empty = c.scale_bullet(req, 0.0);
+    assert_eq!((empty.damage, empty.scale, empty.vel, empty.modifiers.pierce), (1, 1.0, Vec2::X, 0));
+
+    let half = c.scale_bullet(req, 0.5);
+    assert_eq!((half.damage, half.scale, half.modifiers.pierce), (2, 1.5, 1));
+    assert_eq!(half.vel, Vec2::new(1.25, 0.0));
+
+    let full = c.scale_bullet(req, 1.0);
+    assert_eq!((full.damage, full.scale, full.modifiers.pierce), (3, 2.0, 2));
+    assert_eq!(full.vel, Vec2::new(1.5, 0.0));
+}
+
+#[test]
+fn charge_caps_at_full_resets_on_release_and_respects_min_level() {
+    let c = charge();
+    let def = WeaponDef { charge: Some(c), ..default() };
+    let mut state = WeaponState::full(&def);
+
+    state.hold_charge(&c, 0.125);
+    assert_eq!(state.release_charge(&c), None, "below min_level");
+    assert_eq!(state.charge_secs, 0.0);
+
+    for _ in 0..10 {
+        state.hold_charge(&c, 0.25);
+    }
+    assert_eq!(state.release_charge(&c), Some(1.0));
+
+    // No charge builds during a reload.
+    state.magazine = 0;
+    assert!(state.start_reload(&def));
+    state.hold_charge(&c, 0.5);
+    assert_eq!(state.charge_secs, 0.0);
+}
+
+#[test]
+fn charge_weapon_fires_scaled_bullet_on_release_only() {
+    let def = WeaponDef { charge: Some(charge()), ..default() };
+    let (mut world, player) = producer_world(def);
+    let mut vt = Time::<Virtual>::default();
+    vt.advance_by(std::time::Duration::from_secs_f32(0.5));
+    world.insert_resource(vt);
+
+    // Held for one 0.5 s frame: charging, nothing fired.
+    run_system_once(&mut world, request_player_bullets);
+    assert!(world.resource::<Messages<SpawnBulletRequest>>().is_empty());
+    assert_eq!(world.get::<WeaponState>(player).unwrap().charge_secs, 0.5);
+
+    world.resource_mut::<ButtonInput<MouseButton>>().release(MouseButton::Left);
+    run_system_once(&mut world, request_player_bullets);
+
+    let shots: Vec<SpawnBulletRequest> =
+        world.resource::<Messages<SpawnBulletRequest>>().iter_current_update_messages().copied().collect();
+    assert_eq!(shots.len(), 1);
+    assert_eq!((shots[0].damage, shots[0].scale, shots[0].modifiers.pierce), (2, 1.5, 1));
+    assert_eq!(world.get::<WeaponState>(player).unwrap().charge_secs, 0.0);
+}
+
+#[test]
+fn fired_bullet_takes_request_scale_until_refired() {
+    // One-bullet pool: the second request recycles the same entity.
+    let mut world = pool_world(1, OverflowPolicy::RecycleOldest);
+    fire(&mut world, [SpawnBulletRequest { scale: 2.5, ..request(BulletKind::Enemy, 10.0) }]);
+
+    let (tf, bullet) = world.query::<(&Transform, &Bullet)>().single(&world).unwrap();
+    assert_eq!(tf.scale, Vec3::splat(2.5));
+    assert_eq!(bullet.radius(), Bullet::RADIUS * 2.5);
+
+    fire(&mut world, [request(BulletKind::Enemy, 20.0)]);
+
+    let (tf, bullet) = world.query::<(&Transform, &Bullet)>().single(&world).unwrap();
+    assert_eq!(tf.scale, Vec3::ONE);
+    assert_eq!(bullet.radius(), Bullet::RADIUS);
+}
+
+// -----------------------------------------------------------------------------
+// Shootable bullets + bullet cancel
+// -----------------------------------------------------------------------------
+
+fn spawn_orb(world: &mut World, pos: Vec2, destructible: bool) -> Entity {
+    let mods = BulletModifiers { destructible, ..default() };
+    let req = SpawnBulletRequest { modifiers: mods, pos, ..request(BulletKind::Enemy, 0.0) };
+    let mut bullet = Bullet::pooled(BulletKind::Enemy);
+    bullet.reset_for_fire(&req, 1);
+
+    let layers = active_layers(&bullet);
+    world
+        .spawn((
+            PooledBullet,
+            BulletState::Active,
+            bullet,
+            CollisionStamp::default(),
+            Position(pos),
+            LinearVelocity(Vec2::NEG_Y),
+            layers,
+        ))
+        .id()
+}
+
+#[test]
+fn only_destructible_enemy_bullets_accept_player_bullets() {
+    let orb = Bullet { destructible: true, ..Bullet::pooled(BulletKind::Enemy) };
+    let plain = Bullet::pooled(BulletKind::Enemy);
+
+    assert_eq!(active_layers(&orb), active_destructible_enemy_layers());
+    assert_eq!(active_layers(&plain), active_enemy_layers());
+    assert!(active_layers(&orb).filters.has_all(Layer::PlayerBullet));
+    assert!(!active_layers(&plain).filters.has_all(Layer::PlayerBullet));
+}
+
+#[test]
+fn player_bullet_shoots_down_destructible_orb() {
+    let mut world = collision_world();
+    let shot = spawn_player_bullet(&mut world, default(), Vec2::ZERO, Vec2::Y);
+    let orb = spawn_orb(&mut world, Vec2::ZERO, true);
+
+    start(&mut world, orb, shot);
+    run_system_once(&mut world, process_bullet_vs_bullet_collisions);
+
+    assert_eq!(*world.get::<BulletState>(orb).unwrap(), BulletState::PendingReturn);
+    assert_eq!(*world.get::<BulletState>(shot).unwrap(), BulletState::PendingReturn);
+}
+
+#[test]
+fn piercing_shot_survives_orbs_while_plain_bullets_are_ignored() {
+    let mut world = collision_world();
+    let mods = BulletModifiers { pierce: 1, ..default() };
+    let shot = spawn_player_bullet(&mut world, mods, Vec2::ZERO, Vec2::Y);
+    let plain = spawn_orb(&mut world, Vec2::ZERO, false);
+    let orb = spawn_orb(&mut world, Vec2::ZERO, true);
+
+    start(&mut world, shot, plain);
+    start(&mut world, shot, orb);
+    run_system_once(&mut world, process_bullet_vs_bullet_collisions);
+
+    assert_eq!(*world.get::<BulletState>(plain).unwrap(), BulletState::Active);
+    assert_eq!(*world.get::<BulletState>(orb).unwrap(), BulletState::PendingReturn);
+    assert_eq!(*world.get::<BulletState>(shot).unwrap(), BulletState::Active);
+    assert_eq!(world.get::<Bullet>(shot).unwrap().pierce_left, 0);
+}
+
+#[test]
+fn cancel_turns_enemy_bullets_in_radius_into_pickups() {
+    let mut world = World::new();
+    world.init_resource::<Messages<BulletCancel>>();
+    let near = spawn_orb(&mut world, Vec2::new(30.0, 0.0), false);
+    let far = spawn_orb(&mut world, Vec2::new(300.0, 0.0), false);
+    let own = spawn_player_bullet(&mut world, default(), Vec2::ZERO, Vec2::Y);
+
+    world.resource_mut::<Messages<BulletCancel>>().write(BulletCancel { pos: Vec2::ZERO, radius: 100.0 });
+    run_system_once(&mut world, apply_bullet_cancels);
+
+    assert_eq!(*world.get::<BulletState>(near).unwrap(), BulletState::PendingReturn);
+    assert_eq!(*world.get::<BulletState>(far).unwrap(), BulletState::Active);
+    assert_eq!(*world.get::<BulletState>(own).unwrap(), BulletState::Active);
+
+    let pickups: Vec<_> = world.query::<(&ScorePickup, &Transform)>().iter(&world).collect();
+    assert_eq!(pickups.len(), 1);
+    assert_eq!(pickups[0].0.value, PICKUP_VALUE);
+    assert_eq!(pickups[0].1.translation.truncate(), Vec2::new(30.0, 0.0));
+}
+
+#[test]
+fn pickups_home_in_and_are_collected_near_the_player() {
+    let mut world = World::new();
+    world.insert_resource(fixed_time_with_delta(0.1));
+    world.init_resource::<Messages<ScoreCollected>>();
+
+    let player = world.spawn((Player, Transform::default())).id();
+    world.insert_resource(PlayerEntity(Some(player)));
+    let near = world.spawn((ScorePickup { value: 10 }, Transform::from_xyz(50.0, 0.0, 3.0))).id();
+    let far = world.spawn((ScorePickup { value: 10 }, Transform::from_xyz(0.0, 500.0, 3.0))).id();
+
+    run_system_once(&mut world, collect_score_pickups);
+
+    // 700 px/s × 0.1 s: the near pickup arrives, the far one closes 70 px.
+    assert!(world.get_entity(near).is_err());
+    let far_pos = world.get::<Transform>(far).unwrap().translation;
+    assert!((far_pos - Vec3::new(0.0, 430.0, 3.0)).length() < 1e-3);
+
+    let collected: Vec<_> = world
+        .resource::<Messages<ScoreCollected>>()
+        .iter_current_update_messages()
+        .copied()
+        .collect();
+    assert_eq!(collected.len(), 1);
+    assert_eq!(collected[0].value, 10);
+}
+
+// -----------------------------------------------------------------------------
+// Graze
+// -----------------------------------------------------------------------------
+
+#[test]
+fn near_bullet_counts_once_on_leaving_the_zone() {
+    let mut mark = GrazeMark::default();
+    assert!(!mark.step(true, false));
+    assert_eq!(mark, GrazeMark::Near);
+    assert!(!mark.step(true, false));
+
+    assert!(mark.step(false, false));
+    assert_eq!(mark, GrazeMark::Counted);
+
+    // Coming back (e.g. homing) doesn't count again.
+    assert!(!mark.step(true, false));
+    assert!(!mark.step(false, false));
+}
+
+#[test]
+fn touching_the_hurtbox_is_not_a_near_miss() {
+    // Overlapping the hurtbox is a hit in the making.
+    let mut mark = GrazeMark::default();
+    assert!(!mark.step(true, true));
+    assert_eq!(mark, GrazeMark::Clear);
+
+    assert!(!mark.step(false, false));
+    assert_eq!(mark, GrazeMark::Clear);
+}
+
+#[test]
+fn refired_bullet_can_graze_again() {
+    let mut world = pool_world(1, OverflowPolicy::RecycleOldest);
+    fire(&mut world, [request(BulletKind::Enemy, 10.0)]);
+    let bullet = world.query_filtered::<Entity, With<PooledBullet>>().single(&world).unwrap();
+    world.get_mut::<CollisionStamp>(bullet).unwrap().graze = GrazeMark::Counted;
+
+    fire(&mut world, [request(BulletKind::Enemy, 20.0)]);
+
+    assert_eq!(world.get::<CollisionStamp>(bullet).unwrap().graze, GrazeMark::Clear);
+}
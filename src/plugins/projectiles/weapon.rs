@@ -0,0 +1,512 @@
+//! Data-driven weapon definitions.
+//!
+//! # Data flow
+//! ```text
+//!   assets/weapons/*.weapon.ron ──(RonAssetLoader)──> Assets<WeaponDef>
+//!   WeaponLoadout (handles) ──(sync_equipped_weapon)──> Weapon.def on the player
+//!   request_player_bullets ──(WeaponDef::bullet_requests)──> SpawnBulletRequest × pellets
+//!                          └─(WeaponDef::hitscan_requests)─> HitscanRequest × pellets
+//! ```
+//!
+//! `delivery` picks the path: pooled bullets (default), instant hitscan rays, or a beam
+//! that keeps ticking while the trigger is held (one tick per `fire_rate` shot).
+//!
+//! # Charge shots
+//! A weapon with `charge: Some(..)` fires on *release*: holding the trigger accumulates
+//! `WeaponState::charge_secs` and the shot's damage, size, speed and pierce scale with
+//! the charge level (0..=1). The clock is per weapon: `Virtual` pauses with hitstop,
+//! `Real` keeps charging through it.
+//!
+//! The equipped definition is *copied* into the `Weapon` component. The producer hot path
+//! then reads plain data and never touches `Assets<WeaponDef>`; hot-reloading a file only
+//! costs one copy when the asset event arrives.
+//!
+//! # Ammo + cadence
+//! `WeaponState` is the runtime truth for cooldown, magazine, reserve and reload.
+//! It ticks on `Time<Virtual>`, so hitstop (speed 0) freezes both the fire cooldown and
+//! an in-progress reload. Cycling away holsters the state in the loadout; a weapon only
+//! starts full the first time it is drawn in a run.
+//!
+//! ```text
+//!   Ready ──(R pressed / mag empty on fire)──> Reloading { remaining }
+//!   Reloading ──(remaining hits 0: move reserve → magazine)──> Ready
+//! ```
+//!
+//! Charge does not build while reloading, and a release resets it whether or not the
+//! shot was allowed.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::common::ron_asset::RonAsset;
+use crate::common::tunables::Tunables;
+
+use super::components::{Bullet, PlayerEntity};
+use super::messages::{BulletKind, BulletModifiers, HitscanRequest, SpawnBulletRequest};
+
+/// Weapon files bundled with the game, in cycle order.
+pub const DEFAULT_LOADOUT: [&str; 8] = [
+    "weapons/pistol.weapon.ron",
+    "weapons/rifle.weapon.ron",
+    "weapons/smg.weapon.ron",
+    "weapons/shotgun.weapon.ron",
+    "weapons/launcher.weapon.ron",
+    "weapons/railgun.weapon.ron",
+    "weapons/beam.weapon.ron",
+    "weapons/charger.weapon.ron",
+];
+
+/// How a shot reaches its target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum Delivery {
+    /// Pooled physical bullets.
+    #[default]
+    Projectile,
+    /// One instant ray per pellet, reflecting off walls up to `bounces` times.
+    Hitscan { range: f32, bounces: u8 },
+    /// Rays fired every shot while the trigger is held (always automatic); each shot is
+    /// one damage tick and one round of the magazine.
+    Beam { range: f32, bounces: u8 },
+}
+
+/// Which clock a charge accumulates on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum ChargeClock {
+    /// Game time: hitstop/slowmo pause or slow charging.
+    #[default]
+    Virtual,
+    /// Wall-clock time.
+    Real,
+}
+
+/// Release-to-fire charge parameters.
+///
+/// Multipliers are reached at full charge and interpolate linearly from 1 at zero charge.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Charge {
+    /// Seconds of holding to reach full charge.
+    pub full_secs: f32,
+    /// Minimum level (0..=1) a release needs to fire; below it the release is a no-op.
+    pub min_level: f32,
+    pub clock: ChargeClock,
+    pub damage_mult: f32,
+    pub size_mult: f32,
+    pub speed_mult: f32,
+    /// Extra pierce at full charge (scaled by level, rounded down).
+    pub bonus_pierce: u8,
+}
+
+impl Default for Charge {
+    fn default() -> Self {
+        Self {
+            full_secs: 1.0,
+            min_level: 0.0,
+            clock: ChargeClock::Virtual,
+            damage_mult: 4.0,
+            size_mult: 3.0,
+            speed_mult: 1.5,
+            bonus_pierce: 2,
+        }
+    }
+}
+
+impl Charge {
+    /// Charge level in 0..=1 after holding for `held_secs`.
+    #[inline]
+    pub fn level(&self, held_secs: f32) -> f32 {
+        (held_secs / self.full_secs.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+
+    #[inline]
+    fn mult(full: f32, level: f32) -> f32 {
+        1.0 + (full - 1.0) * level
+    }
+
+    /// Damage at `level`, rounded, never below the uncharged damage.
+    #[inline]
+    pub fn damage(&self, base: i32, level: f32) -> i32 {
+        ((base as f32 * Self::mult(self.damage_mult, level)).round() as i32).max(base)
+    }
+
+    #[inline]
+    pub fn pierce(&self, base: u8, level: f32) -> u8 {
+        base.saturating_add((self.bonus_pierce as f32 * level) as u8)
+    }
+
+    /// Apply the charge at `level` to one bullet request.
+    pub fn scale_bullet(&self, req: SpawnBulletRequest, level: f32) -> SpawnBulletRequest {
+        let mut req = req;
+        req.damage = self.damage(req.damage, level);
+        req.vel *= Self::mult(self.speed_mult, level);
+        req.scale *= Self::mult(self.size_mult, level);
+        req.modifiers.pierce = self.pierce(req.modifiers.pierce, level);
+        req
+    }
+
+    /// Apply the charge at `level` to one ray (damage + pierce; rays have no size or speed).
+    pub fn scale_ray(&self, ray: HitscanRequest, level: f32) -> HitscanRequest {
+        let mut ray = ray;
+        ray.damage = self.damage(ray.damage, level);
+        ray.modifiers.pierce = self.pierce(ray.modifiers.pierce, level);
+        ray
+    }
+}
+
+/// Designer-facing weapon description.
+///
+/// Missing fields fall back to `Default` (the built-in pistol), so files only need to
+/// state what differs.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WeaponDef {
+    pub name: String,
+    /// Shots per second (enforced by the fire cooldown).
+    pub fire_rate: f32,
+    /// Bullets emitted per shot.
+    pub pellets: u8,
+    /// Total fan angle across all pellets, in degrees.
+    pub spread_deg: f32,
+    /// Bullet speed in pixels per second.
+    pub speed: f32,
+    pub damage: i32,
+    /// Pierce / armour-piercing / ricochet applied to every pellet.
+    pub modifiers: BulletModifiers,
+    pub delivery: Delivery,
+    /// Fire on release with charge scaling (`None` = fire on press).
+    pub charge: Option<Charge>,
+    /// Distance from the shooter's centre to the bullet spawn point, in pixels.
+    pub muzzle_offset: f32,
+    /// Hold to fire (true) or one shot per click (false).
+    pub automatic: bool,
+    pub magazine_size: u16,
+    /// Rounds carried outside the magazine when the weapon is equipped.
+    pub reserve_ammo: u16,
+    pub reload_secs: f32,
+    /// Seconds of virtual time before a bullet expires.
+    pub lifetime_secs: f32,
+    /// Pixels a bullet may travel before it expires.
+    pub max_range: f32,
+}
+
+impl Default for WeaponDef {
+    fn default() -> Self {
+        Self {
+            name: "Pistol".into(),
+            fire_rate: 4.0,
+            pellets: 1,
+            spread_deg: 0.0,
+            speed: Tunables::default().bullet_speed,
+            damage: 1,
+            modifiers: BulletModifiers::default(),
+            delivery: Delivery::Projectile,
+            charge: None,
+            muzzle_offset: 18.0,
+            automatic: false,
+            magazine_size: 12,
+            reserve_ammo: 96,
+            reload_secs: 1.0,
+            lifetime_secs: Bullet::DEFAULT_LIFETIME_SECS,
+            max_range: Bullet::DEFAULT_MAX_RANGE,
+        }
+    }
+}
+
+impl RonAsset for WeaponDef {
+    const EXTENSIONS: &'static [&'static str] = &["weapon.ron"];
+}
+
+impl WeaponDef {
+    /// Fire while the button is held (rather than once per click).
+    #[inline]
+    pub fn is_held(&self) -> bool {
+        self.automatic || matches!(self.delivery, Delivery::Beam { .. })
+    }
+
+    /// Per-pellet directions for one trigger pull around the normalized `dir`.
+    ///
+    /// Pellets are spread evenly across `spread_deg` (no RNG), so a given aim direction
+    /// always produces the same fan.
+    fn pellet_dirs(&self, dir: Vec2) -> impl Iterator<Item = Vec2> {
+        let pellets = self.pellets.max(1);
+        let spread = self.spread_deg.to_radians();
+        let step = if pellets > 1 { spread / (pellets - 1) as f32 } else { 0.0 };
+        let first = if pellets > 1 { -spread * 0.5 } else { 0.0 };
+
+        (0..pellets).map(move |i| Vec2::from_angle(first + step * i as f32).rotate(dir))
+    }
+
+    /// Expand one trigger pull into per-pellet spawn requests. `dir` must be normalized.
+    pub fn bullet_requests(
+        &self,
+        kind: BulletKind,
+        origin: Vec2,
+        dir: Vec2,
+        owner: Option<Entity>,
+    ) -> impl Iterator<Item = SpawnBulletRequest> + '_ {
+        let pos = origin + dir * self.muzzle_offset;
+
+        self.pellet_dirs(dir).map(move |pellet_dir| {
+            SpawnBulletRequest {
+                kind,
+                pos,
+                vel: pellet_dir * self.speed,
+                damage: self.damage,
+                modifiers: self.modifiers,
+                lifetime: self.lifetime_secs,
+                max_range: self.max_range,
+                owner,
+                scale: 1.0,
+                fired_at: None,
+            }
+        })
+    }
+
+    /// Expand one trigger pull (or beam tick) into per-pellet rays. `dir` must be normalized.
+    ///
+    /// Rays start at `origin` rather than the muzzle so a shooter hugging a wall can't
+    /// fire through it. A `Projectile` weapon gets straight rays of `max_range`.
+    pub fn hitscan_requests(
+        &self,
+        origin: Vec2,
+        dir: Vec2,
+        owner: Option<Entity>,
+    ) -> impl Iterator<Item = HitscanRequest> + '_ {
+        let (range, bounces, beam) = match self.delivery {
+            Delivery::Projectile => (self.max_range, 0, false),
+            Delivery::Hitscan { range, bounces } => (range, bounces, false),
+            Delivery::Beam { range, bounces } => (range, bounces, true),
+        };
+
+        self.pellet_dirs(dir).map(move |dir| HitscanRequest {
+            origin,
+            dir,
+            range,
+            bounces,
+            damage: self.damage,
+            modifiers: self.modifiers,
+            owner,
+            beam,
+        })
+    }
+}
+
+/// Equipped weapon: the active definition plus the asset it was copied from.
+///
+/// `source == None` means the built-in default (e.g. before assets finish loading).
+#[derive(Component, Debug, Clone, Default)]
+pub struct Weapon {
+    pub def: WeaponDef,
+    pub source: Option<Handle<WeaponDef>>,
+}
+
+/// Reload state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReloadState {
+    #[default]
+    Ready,
+    /// Seconds of virtual time until the magazine is refilled.
+    Reloading { remaining: f32 },
+}
+
+/// Runtime ammo + cadence state of the equipped weapon.
+///
+/// Public so HUD and tests can read it straight off the player entity.
+#[derive(Component, Debug, Clone, PartialEq, Default)]
+pub struct WeaponState {
+    /// Seconds of virtual time until the next shot is allowed.
+    pub cooldown: f32,
+    pub magazine: u16,
+    pub reserve: u16,
+    pub reload: ReloadState,
+    /// Seconds the trigger has been held on a charge weapon (on the weapon's clock).
+    pub charge_secs: f32,
+}
+
+impl WeaponState {
+    /// Fresh state for a newly equipped weapon: full magazine + full reserve.
+    pub fn full(def: &WeaponDef) -> Self {
+        Self {
+            cooldown: 0.0,
+            magazine: def.magazine_size,
+            reserve: def.reserve_ammo,
+            reload: ReloadState::Ready,
+            charge_secs: 0.0,
+        }
+    }
+
+    #[inline]
+    pub fn is_reloading(&self) -> bool {
+        matches!(self.reload, ReloadState::Reloading { .. })
+    }
+
+    /// Advance cooldown and reload by `dt` seconds of virtual time.
+    pub fn tick(&mut self, def: &WeaponDef, dt: f32) {
+        self.cooldown = (self.cooldown - dt).max(0.0);
+
+        if let ReloadState::Reloading { remaining } = &mut self.reload {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                let wanted = def.magazine_size.saturating_sub(self.magazine);
+                let moved = wanted.min(self.reserve);
+                self.magazine += moved;
+                self.reserve -= moved;
+                self.reload = ReloadState::Ready;
+            }
+        }
+    }
+
+    /// Begin a reload. Returns false if already reloading, full, or out of reserve.
+    pub fn start_reload(&mut self, def: &WeaponDef) -> bool {
+        if self.is_reloading() || self.magazine >= def.magazine_size || self.reserve == 0 {
+            return false;
+        }
+        self.reload = ReloadState::Reloading { remaining: def.reload_secs };
+        true
+    }
+
+    /// Accumulate charge while the trigger is held (not while reloading).
+    #[inline]
+    pub fn hold_charge(&mut self, charge: &Charge, dt: f32) {
+        if !self.is_reloading() {
+            self.charge_secs = (self.charge_secs + dt).min(charge.full_secs);
+        }
+    }
+
+    /// Trigger released: reset the charge and return its level if it is enough to fire.
+    pub fn release_charge(&mut self, charge: &Charge) -> Option<f32> {
+        let level = charge.level(self.charge_secs);
+        self.charge_secs = 0.0;
+        (level >= charge.min_level).then_some(level)
+    }
+
+    /// Consume one round if the weapon is ready. Returns whether a shot happens.
+    ///
+    /// Pulling the trigger on an empty magazine starts a reload instead.
+    pub fn try_fire(&mut self, def: &WeaponDef) -> bool {
+        if self.is_reloading() || self.cooldown > 0.0 {
+            return false;
+        }
+        if self.magazine == 0 {
+            self.start_reload(def);
+            return false;
+        }
+
+        self.magazine -= 1;
+        self.cooldown = 1.0 / def.fire_rate.max(0.001);
+        true
+    }
+}
+
+/// Weapons the player can cycle through.
+#[derive(Resource, Debug, Default)]
+pub struct WeaponLoadout {
+    pub weapons: Vec<Handle<WeaponDef>>,
+    /// Holstered state per slot (`None` = not drawn yet this run).
+    pub states: Vec<Option<WeaponState>>,
+    pub current: usize,
+}
+
+impl WeaponLoadout {
+    #[inline]
+    pub fn current(&self) -> Option<&Handle<WeaponDef>> {
+        self.weapons.get(self.current)
+    }
+
+    #[inline]
+    pub fn cycle(&mut self) {
+        if !self.weapons.is_empty() {
+            self.current = (self.current + 1) % self.weapons.len();
+        }
+    }
+
+    #[inline]
+    pub fn slot_of(&self, handle: &Handle<WeaponDef>) -> Option<usize> {
+        self.weapons.iter().position(|h| h == handle)
+    }
+
+    /// Holster `held` in slot `from` and draw the current slot's state.
+    ///
+    /// A slot starts full only on its first draw, so cycling can't refill ammo. Holstered
+    /// weapons keep their magazine, reserve and reload progress; a charge is dropped.
+    pub fn swap_state(&mut self, from: Option<usize>, held: WeaponState, def: &WeaponDef) -> WeaponState {
+        self.states.resize(self.weapons.len(), None);
+        if let Some(slot) = from.and_then(|i| self.states.get_mut(i)) {
+            *slot = Some(WeaponState { charge_secs: 0.0, ..held });
+        }
+        self.states[self.current].take().unwrap_or_else(|| WeaponState::full(def))
+    }
+}
+
+pub fn load_default_loadout(asset_server: Res<AssetServer>, mut loadout: ResMut<WeaponLoadout>) {
+    loadout.weapons = DEFAULT_LOADOUT.iter().map(|path| asset_server.load(*path)).collect();
+    loadout.states.clear();
+    loadout.current = 0;
+}
+
+/// New run: every weapon is drawn full again.
+pub fn reset_loadout_states(mut loadout: ResMut<WeaponLoadout>) {
+    loadout.states.clear();
+}
+
+pub fn cycle_weapon(keys: Option<Res<ButtonInput<KeyCode>>>, mut loadout: ResMut<WeaponLoadout>) {
+    let Some(keys) = keys else { return; };
+    if keys.just_pressed(KeyCode::KeyE) {
+        loadout.cycle();
+    }
+}
+
+/// Tick cooldown + reload on virtual time and handle the manual reload key.
+pub fn update_weapon_state(
+    time: Res<Time<Virtual>>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    player_e: Res<PlayerEntity>,
+    mut q_weapon: Query<(&Weapon, &mut WeaponState)>,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let (weapon, mut state) = q_weapon.get_mut(player).expect("Player missing Weapon/WeaponState");
+
+    state.tick(&weapon.def, time.delta_secs());
+
+    if keys.is_some_and(|keys| keys.just_pressed(KeyCode::KeyR)) {
+        state.start_reload(&weapon.def);
+    }
+}
+
+/// Copy the selected loadout definition into the player's `Weapon`.
+///
+/// Runs when the selection changes or the file is (re)loaded. Until the asset is
+/// available the previous definition stays equipped. Switching weapons swaps
+/// `WeaponState` through the loadout (`WeaponLoadout::swap_state`); a hot-reload keeps it.
+pub fn sync_equipped_weapon(
+    mut asset_events: MessageReader<AssetEvent<WeaponDef>>,
+    defs: Res<Assets<WeaponDef>>,
+    mut loadout: ResMut<WeaponLoadout>,
+    player_e: Res<PlayerEntity>,
+    mut q_weapon: Query<(&mut Weapon, &mut WeaponState)>,
+) {
+    let Some(handle) = loadout.current().cloned() else { return; };
+
+    let reloaded = asset_events
+        .read()
+        .filter(|ev| ev.is_loaded_with_dependencies(&handle) || ev.is_modified(&handle))
+        .count()
+        > 0;
+
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+    let (mut weapon, mut state) = q_weapon.get_mut(player).expect("Player missing Weapon/WeaponState");
+
+    let swapping = weapon.source.as_ref() != Some(&handle);
+    if !swapping && !reloaded {
+        return;
+    }
+
+    if let Some(def) = defs.get(&handle) {
+        if swapping {
+            let from = weapon.source.as_ref().and_then(|h| loadout.slot_of(h));
+            *state = loadout.swap_state(from, std::mem::take(&mut *state), def);
+        }
+        weapon.def = def.clone();
+        weapon.source = Some(handle);
+    }
+}
@@ -0,0 +1,187 @@
+//! Score plugin: run statistics and the combo multiplier.
+//!
+//! # Data flow
+//! ```text
+//!   request_player_bullets          ──> RunStats::shots_fired (one per bullet / ray)
+//!   bullet resolve + resolve_hitscan ──> RunStats::hits (one per shot that connected)
+//!   enemy_death_trigger             ──> RunStats::record_kill (score × combo, combo boost)
+//!   record_damage_events  (DamageEvent: armour breaks, damage dealt / taken)
+//!   record_score_messages (ScoreCollected pickups, Grazed near misses)
+//!   tick_combo            (fixed step: combo window, then decay back to ×1)
+//! ```
+//!
+//! `RunStats` is one plain resource, reset on entering `InGame`: a HUD, a results screen
+//! or a headless test reads it directly instead of re-deriving totals from messages.
+//!
+//! # Combo
+//! - Every kill opens (or refreshes) a `COMBO_WINDOW_SECS` window and raises the
+//!   multiplier by `COMBO_STEP`, up to `COMBO_MAX`. Kills inside the window extend the chain.
+//! - Once the window closes, the chain ends and the multiplier decays toward ×1 at
+//!   `COMBO_DECAY_PER_SEC`.
+//! - Losing health drops the combo straight back to ×1.
+//!
+//! All points go through `RunStats::award`, so pickups and grazes profit from the combo too.
+//! Timers run on the fixed (virtual-driven) clock: hitstop and slowmo pause the combo.
+
+use bevy::prelude::*;
+
+use crate::common::state::GameState;
+use crate::plugins::combat::{apply_damage, DamageEvent};
+use crate::plugins::projectiles::components::{Enemy, PlayerEntity};
+use crate::plugins::projectiles::graze::detect_grazes;
+use crate::plugins::projectiles::messages::{Grazed, ScoreCollected};
+
+/// Points for a kill, before the combo multiplier.
+pub const KILL_SCORE: u32 = 100;
+/// Points for breaking an enemy's armour, before the combo multiplier.
+pub const ARMOUR_BREAK_SCORE: u32 = 25;
+
+pub const COMBO_WINDOW_SECS: f32 = 2.5;
+pub const COMBO_STEP: f32 = 0.25;
+pub const COMBO_MAX: f32 = 8.0;
+pub const COMBO_DECAY_PER_SEC: f32 = 0.5;
+
+/// Kill chain and the score multiplier it drives.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Combo {
+    pub multiplier: f32,
+    /// Kills since the window last closed.
+    pub chain: u32,
+    /// Seconds of fixed time left before the chain ends.
+    pub window: f32,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self { multiplier: 1.0, chain: 0, window: 0.0 }
+    }
+}
+
+impl Combo {
+    pub fn on_kill(&mut self) {
+        self.chain = if self.window > 0.0 { self.chain + 1 } else { 1 };
+        self.multiplier = (self.multiplier + COMBO_STEP).min(COMBO_MAX);
+        self.window = COMBO_WINDOW_SECS;
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if self.window > 0.0 {
+            self.window = (self.window - dt).max(0.0);
+            return;
+        }
+        self.chain = 0;
+        self.multiplier = (self.multiplier - COMBO_DECAY_PER_SEC * dt).max(1.0);
+    }
+}
+
+/// Totals for the current run.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunStats {
+    pub score: u64,
+    pub kills: u32,
+    pub armour_breaks: u32,
+    /// Player bullets and rays fired (a shotgun blast is one per pellet).
+    pub shots_fired: u32,
+    /// Shots that hit at least one enemy (pierce and splash don't count twice).
+    pub hits: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub combo: Combo,
+}
+
+impl RunStats {
+    /// Hits per shot fired, in [0..1] (0 before the first shot).
+    #[inline]
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
+
+    /// Add `points` scaled by the current combo multiplier; returns the points added.
+    pub fn award(&mut self, points: u32) -> u64 {
+        let scaled = (points as f32 * self.combo.multiplier).round() as u64;
+        self.score += scaled;
+        scaled
+    }
+
+    /// Score a kill at the current multiplier, then boost the combo.
+    pub fn record_kill(&mut self, points: u32) {
+        self.kills += 1;
+        self.award(points);
+        self.combo.on_kill();
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<RunStats>()
+        .add_systems(OnEnter(GameState::InGame), reset_run_stats)
+        .add_systems(
+            FixedPostUpdate,
+            (
+                record_damage_events.after(apply_damage),
+                record_score_messages.after(detect_grazes),
+                tick_combo,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+/// Fold damage outcomes into the run totals.
+///
+/// Enemy targets count as damage dealt (and armour breaks score); the player's own
+/// health loss counts as damage taken and ends the combo.
+fn record_damage_events(
+    mut events: MessageReader<DamageEvent>,
+    player_e: Res<PlayerEntity>,
+    q_enemy: Query<(), With<Enemy>>,
+    mut stats: ResMut<RunStats>,
+) {
+    let player = player_e.0.expect("PlayerEntity not set (spawn invariant violated)");
+
+    for ev in events.read() {
+        let amount = ev.amount.max(0) as u32;
+
+        if ev.target == player {
+            stats.damage_taken += amount;
+            if amount > 0 {
+                stats.combo = Combo::default();
+            }
+            continue;
+        }
+
+        if !q_enemy.contains(ev.target) { continue; }
+
+        stats.damage_dealt += amount;
+        if ev.armour_broken {
+            stats.armour_breaks += 1;
+            stats.award(ARMOUR_BREAK_SCORE);
+        }
+    }
+}
+
+fn record_score_messages(
+    mut collected: MessageReader<ScoreCollected>,
+    mut grazed: MessageReader<Grazed>,
+    mut stats: ResMut<RunStats>,
+) {
+    for pickup in collected.read() {
+        stats.award(pickup.value);
+    }
+    for graze in grazed.read() {
+        stats.award(graze.points);
+    }
+}
+
+fn tick_combo(time: Res<Time<Fixed>>, mut stats: ResMut<RunStats>) {
+    stats.combo.tick(time.delta_secs());
+}
+
+#[cfg(test)]
+mod tests;
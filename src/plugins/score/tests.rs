@@ -0,0 +1,129 @@
+//! Unit tests for run statistics and the combo.
+
+use bevy::ecs::message::Messages;
+use bevy::prelude::*;
+
+use crate::common::test_utils::run_system_once;
+use crate::plugins::combat::DamageType;
+
+use super::*;
+
+fn event(target: Entity, amount: i32, armour_broken: bool) -> DamageEvent {
+    DamageEvent {
+        source: None,
+        target,
+        amount,
+        damage_type: DamageType::Kinetic,
+        position: Vec2::ZERO,
+        impulse: Vec2::ZERO,
+        was_armour_hit: armour_broken,
+        armour_broken,
+        was_kill: false,
+    }
+}
+
+fn stats_world() -> (World, Entity, Entity) {
+    let mut world = World::new();
+    world.init_resource::<RunStats>();
+    world.init_resource::<Messages<DamageEvent>>();
+    world.init_resource::<Messages<ScoreCollected>>();
+    world.init_resource::<Messages<Grazed>>();
+
+    let player = world.spawn_empty().id();
+    world.insert_resource(PlayerEntity(Some(player)));
+    let enemy = world.spawn(Enemy).id();
+    (world, player, enemy)
+}
+
+#[test]
+fn accuracy_is_hits_per_shot() {
+    let mut stats = RunStats::default();
+    assert_eq!(stats.accuracy(), 0.0);
+
+    stats.shots_fired = 8;
+    stats.hits = 6;
+    assert_eq!(stats.accuracy(), 0.75);
+}
+
+#[test]
+fn chained_kills_raise_the_multiplier_up_to_the_cap() {
+    let mut stats = RunStats::default();
+
+    stats.record_kill(100);
+    stats.record_kill(100);
+    assert_eq!(stats.score, 100 + 125);
+    assert_eq!((stats.kills, stats.combo.chain), (2, 2));
+    assert_eq!(stats.combo.multiplier, 1.0 + 2.0 * COMBO_STEP);
+
+    for _ in 0..100 {
+        stats.record_kill(0);
+    }
+    assert_eq!(stats.combo.multiplier, COMBO_MAX);
+}
+
+#[test]
+fn combo_holds_through_the_window_then_decays_to_one() {
+    let mut combo = Combo::default();
+    combo.on_kill();
+    combo.on_kill();
+    let peak = combo.multiplier;
+
+    combo.tick(COMBO_WINDOW_SECS - 0.5);
+    assert_eq!((combo.multiplier, combo.chain), (peak, 2));
+
+    combo.tick(0.5);
+    combo.tick(0.5);
+    assert_eq!(combo.chain, 0);
+    assert_eq!(combo.multiplier, peak - COMBO_DECAY_PER_SEC * 0.5);
+
+    combo.tick(60.0);
+    assert_eq!(combo.multiplier, 1.0);
+
+    // A kill after the window starts a new chain.
+    combo.on_kill();
+    assert_eq!(combo.chain, 1);
+}
+
+#[test]
+fn damage_events_split_into_dealt_taken_and_armour_breaks() {
+    let (mut world, player, enemy) = stats_world();
+    world.resource_mut::<RunStats>().record_kill(0);
+    let bystander = world.spawn_empty().id();
+
+    world.resource_mut::<Messages<DamageEvent>>().write_batch([
+        event(enemy, 3, false),
+        event(enemy, 0, true),
+        event(bystander, 7, false),
+        event(player, 2, false),
+    ]);
+    run_system_once(&mut world, record_damage_events);
+
+    let stats = world.resource::<RunStats>();
+    assert_eq!((stats.damage_dealt, stats.damage_taken, stats.armour_breaks), (3, 2, 1));
+    // The armour break scored at ×1.25 before the player's hit reset the combo.
+    assert_eq!(stats.score, 31);
+    assert_eq!(stats.combo, Combo::default());
+}
+
+#[test]
+fn blocked_player_hits_keep_the_combo() {
+    let (mut world, player, _) = stats_world();
+    world.resource_mut::<RunStats>().record_kill(0);
+
+    world.resource_mut::<Messages<DamageEvent>>().write(event(player, 0, true));
+    run_system_once(&mut world, record_damage_events);
+
+    assert_eq!(world.resource::<RunStats>().combo.chain, 1);
+}
+
+#[test]
+fn pickups_and_grazes_score_with_the_combo() {
+    let (mut world, _, _) = stats_world();
+    world.resource_mut::<RunStats>().combo.multiplier = 2.0;
+
+    world.resource_mut::<Messages<ScoreCollected>>().write(ScoreCollected { value: 10, pos: Vec2::ZERO });
+    world.resource_mut::<Messages<Grazed>>().write(Grazed { pos: Vec2::ZERO, points: 5 });
+    run_system_once(&mut world, record_score_messages);
+
+    assert_eq!(world.resource::<RunStats>().score, 30);
+}
@@ -1,4 +1,7 @@
-//! World plugin: spawns arena walls.
+//! World plugin: spawns arena walls, publishes the arena extents (`ArenaBounds`) and
+//! keeps the navigation grid (`nav::NavGrid`) in step with static obstacles.
+
+pub mod nav;
 
 use avian2d::prelude::*;
 use bevy::prelude::*;
@@ -11,9 +14,42 @@ const TILE: i32 = 64;
 const HALF_W: i32 = TILE * 16;
 const HALF_H: i32 = TILE * 9;
 
+/// Playable arena extents (inner faces of the walls), in world pixels.
+///
+/// Single source of truth for "inside the arena": other plugins read this instead of
+/// re-deriving it from wall geometry.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ArenaBounds {
+    pub rect: Rect,
+}
+
+impl Default for ArenaBounds {
+    fn default() -> Self {
+        Self {
+            rect: Rect::from_center_half_size(Vec2::ZERO, Vec2::new(HALF_W as f32, HALF_H as f32)),
+        }
+    }
+}
+
+impl ArenaBounds {
+    /// True if `p` is inside the arena grown by `margin` on every side.
+    #[inline]
+    pub fn contains_with_margin(&self, p: Vec2, margin: f32) -> bool {
+        self.rect.inflate(margin).contains(p)
+    }
+}
+
 pub fn plugin(app: &mut App) {
+    app.insert_resource(ArenaBounds::default());
     app.add_systems(OnEnter(GameState::InGame), spawn_arena);
     app.add_systems(OnEnter(GameState::InGame), spawn_floor);
+
+    app.init_resource::<nav::NavGrid>()
+        .add_systems(OnEnter(GameState::InGame), nav::reset_nav_grid)
+        .add_systems(
+            FixedPostUpdate,
+            nav::sync_nav_grid.run_if(in_state(GameState::InGame)),
+        );
 }
 
 fn spawn_arena(mut commands: Commands) {
@@ -0,0 +1,391 @@
+//! Navigation grid: walkable cells derived from static `Layer::World` colliders, and A*
+//! paths between world points.
+//!
+//! # Data flow
+//! ```text
+//!   static World colliders (added / moved / removed)
+//!       sync_nav_grid ──> NavGrid (per-cell obstacle counts, version bump on change)
+//!   any system: Res<NavGrid>::find_path(from, to) ──> waypoints (world px)
+//!               Res<NavGrid>::flow_field(goal)    ──> FlowField (direction per cell)
+//! ```
+//!
+//! The grid covers `ArenaBounds` in `TILE`-sized cells. Each obstacle remembers the cells
+//! its AABB covered, so adding, moving or removing one walls only touches those cells
+//! instead of rebuilding the whole grid. `NavGrid::version` changes whenever a cell does;
+//! callers that cache paths (e.g. `enemies::movement::NavPath`) recompute on a mismatch.
+//!
+//! # Paths
+//! 8-connected A* with an octile heuristic. Diagonal steps never cut a blocked corner,
+//! so a body one cell wide can follow the path. Waypoints are cell centres with
+//! collinear runs merged; the last one is the requested goal point itself.
+//!
+//! # Flow fields
+//! For many agents sharing one goal, `NavGrid::flow_field` runs a single Dijkstra pass
+//! from the goal and stores, per cell, the direction of the cheapest neighbour. Looking
+//! up a direction is then O(1) per agent, whatever the crowd size.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::plugins::projectiles::layers::Layer;
+
+use super::{ArenaBounds, TILE};
+
+/// Cell size in world pixels (one floor tile).
+pub const NAV_CELL: f32 = TILE as f32;
+
+/// A* step costs (×10 so diagonals stay integers).
+const STRAIGHT: u32 = 10;
+const DIAGONAL: u32 = 14;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Walkability of the arena, in cells.
+#[derive(Resource, Debug, Clone)]
+pub struct NavGrid {
+    /// World position of the grid's bottom-left corner.
+    origin: Vec2,
+    cols: i32,
+    rows: i32,
+    /// Obstacles covering each cell (row-major); 0 = walkable.
+    blockers: Vec<u16>,
+    /// Cells each obstacle covers, as an inclusive (min, max) cell rectangle.
+    footprints: HashMap<Entity, (IVec2, IVec2)>,
+    version: u64,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self::new(ArenaBounds::default().rect, NAV_CELL)
+    }
+}
+
+impl NavGrid {
+    /// An empty (all walkable) grid covering `rect` with square cells of `cell` px.
+    pub fn new(rect: Rect, cell: f32) -> Self {
+        let cols = (rect.width() / cell).ceil().max(1.0) as i32;
+        let rows = (rect.height() / cell).ceil().max(1.0) as i32;
+        Self {
+            origin: rect.min,
+            cols,
+            rows,
+            blockers: vec![0; (cols * rows) as usize],
+            footprints: HashMap::new(),
+            version: 0,
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> IVec2 {
+        IVec2::new(self.cols, self.rows)
+    }
+
+    /// Changes whenever any cell's walkability does.
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    #[inline]
+    fn in_bounds(&self, c: IVec2) -> bool {
+        c.x >= 0 && c.y >= 0 && c.x < self.cols && c.y < self.rows
+    }
+
+    #[inline]
+    fn index(&self, c: IVec2) -> usize {
+        (c.y * self.cols + c.x) as usize
+    }
+
+    /// Cell containing `p`, or `None` outside the grid.
+    pub fn cell_at(&self, p: Vec2) -> Option<IVec2> {
+        let c = ((p - self.origin) / NAV_CELL).floor().as_ivec2();
+        self.in_bounds(c).then_some(c)
+    }
+
+    #[inline]
+    pub fn cell_center(&self, c: IVec2) -> Vec2 {
+        self.origin + (c.as_vec2() + Vec2::splat(0.5)) * NAV_CELL
+    }
+
+    /// Out-of-grid cells count as blocked.
+    #[inline]
+    pub fn is_blocked(&self, c: IVec2) -> bool {
+        !self.in_bounds(c) || self.blockers[self.index(c)] > 0
+    }
+
+    /// Cells overlapped by `aabb` (touching an edge doesn't count), clamped to the grid.
+    fn covered_cells(&self, aabb: Rect) -> Option<(IVec2, IVec2)> {
+        let min = ((aabb.min - self.origin) / NAV_CELL).floor().as_ivec2();
+        let max = ((aabb.max - self.origin) / NAV_CELL).ceil().as_ivec2() - IVec2::ONE;
+        let min = min.max(IVec2::ZERO);
+        let max = max.min(self.size() - IVec2::ONE);
+        (min.x <= max.x && min.y <= max.y).then_some((min, max))
+    }
+
+    fn mark(&mut self, (min, max): (IVec2, IVec2), add: bool) {
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let i = self.index(IVec2::new(x, y));
+                if add {
+                    self.blockers[i] += 1;
+                } else {
+                    self.blockers[i] -= 1;
+                }
+            }
+        }
+        self.version += 1;
+    }
+
+    /// Add `obstacle` covering `aabb`, or move it there if already known.
+    pub fn set_obstacle(&mut self, obstacle: Entity, aabb: Rect) {
+        let cells = self.covered_cells(aabb);
+        if self.footprints.get(&obstacle).copied() == cells { return; }
+
+        self.remove_obstacle(obstacle);
+        if let Some(cells) = cells {
+            self.mark(cells, true);
+            self.footprints.insert(obstacle, cells);
+        }
+    }
+
+    /// Forget `obstacle`; unknown entities are ignored.
+    pub fn remove_obstacle(&mut self, obstacle: Entity) {
+        if let Some(cells) = self.footprints.remove(&obstacle) {
+            self.mark(cells, false);
+        }
+    }
+
+    /// Drop every obstacle (the arena is rebuilt on entering `InGame`).
+    pub fn clear(&mut self) {
+        self.blockers.fill(0);
+        self.footprints.clear();
+        self.version += 1;
+    }
+
+    /// Waypoints from `from` to `to`, or `None` if either is off the grid or `to` is
+    /// unreachable.
+    ///
+    /// The end cells may be blocked: a body shoved into a wall corner still finds its way
+    /// out, and a goal beside a partly blocking obstacle is still reached. The start cell
+    /// is not part of the result.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.cell_at(from)?;
+        let goal = self.cell_at(to)?;
+        if start == goal { return Some(vec![to]); }
+
+        let cells = self.astar(start, goal)?;
+
+        // Keep only the cells where the direction changes, then end on the exact goal.
+        let mut waypoints: Vec<Vec2> = cells
+            .windows(3)
+            .filter(|w| w[1] - w[0] != w[2] - w[1])
+            .map(|w| self.cell_center(w[1]))
+            .collect();
+        waypoints.push(to);
+        Some(waypoints)
+    }
+
+    /// Cost of the step from `c` by `d` (one of `NEIGHBOURS`), or `None` if the target is
+    /// blocked (unless it is `allow`) or a diagonal would cut a blocked corner.
+    #[inline]
+    fn step_cost(&self, c: IVec2, d: IVec2, allow: Option<IVec2>) -> Option<u32> {
+        let next = c + d;
+        if !self.in_bounds(next) || (self.is_blocked(next) && Some(next) != allow) { return None; }
+        if d.x == 0 || d.y == 0 { return Some(STRAIGHT); }
+
+        let cuts_corner = self.is_blocked(c + IVec2::new(d.x, 0)) || self.is_blocked(c + IVec2::new(0, d.y));
+        (!cuts_corner).then_some(DIAGONAL)
+    }
+
+    /// Cells from `start` to `goal`, both included.
+    fn astar(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        let n = self.blockers.len();
+        let mut cost = vec![u32::MAX; n];
+        let mut came_from: Vec<Option<IVec2>> = vec![None; n];
+        let mut open = BinaryHeap::new();
+
+        let heuristic = |c: IVec2| {
+            let d = (c - goal).abs();
+            STRAIGHT * d.x.max(d.y) as u32 + (DIAGONAL - STRAIGHT) * d.x.min(d.y) as u32
+        };
+
+        cost[self.index(start)] = 0;
+        open.push(Reverse((heuristic(start), 0u32, start.x, start.y)));
+
+        while let Some(Reverse((_, g, x, y))) = open.pop() {
+            let c = IVec2::new(x, y);
+            if c == goal {
+                let mut path = vec![goal];
+                let mut at = goal;
+                while let Some(prev) = came_from[self.index(at)] {
+                    path.push(prev);
+                    at = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            // Stale heap entry: a cheaper route to `c` was already expanded.
+            if g > cost[self.index(c)] { continue; }
+
+            for d in NEIGHBOURS {
+                let next = c + d;
+                let Some(step) = self.step_cost(c, d, Some(goal)) else { continue; };
+                let g_next = g + step;
+                let i = self.index(next);
+                if g_next >= cost[i] { continue; }
+
+                cost[i] = g_next;
+                came_from[i] = Some(c);
+                open.push(Reverse((g_next + heuristic(next), g_next, next.x, next.y)));
+            }
+        }
+        None
+    }
+
+    /// Flow field toward `goal`: one Dijkstra pass over the whole grid, shared by every
+    /// agent heading there.
+    ///
+    /// Empty (every direction `None`) if `goal` is off the grid.
+    pub fn flow_field(&self, goal: Vec2) -> FlowField {
+        let mut field = FlowField {
+            origin: self.origin,
+            cols: self.cols,
+            rows: self.rows,
+            goal: self.cell_at(goal),
+            dirs: vec![None; self.blockers.len()],
+        };
+        let Some(goal) = field.goal else { return field; };
+
+        // Distances from the goal (steps are symmetric, so this is also the cost to it).
+        let mut cost = vec![u32::MAX; self.blockers.len()];
+        let mut open = BinaryHeap::new();
+        cost[self.index(goal)] = 0;
+        open.push(Reverse((0u32, goal.x, goal.y)));
+
+        while let Some(Reverse((g, x, y))) = open.pop() {
+            let c = IVec2::new(x, y);
+            if g > cost[self.index(c)] { continue; }
+            // A blocked goal cell (player against a pillar) still spreads to its neighbours.
+            if self.is_blocked(c) && c != goal { continue; }
+
+            for d in NEIGHBOURS {
+                let Some(step) = self.step_cost(c, d, None) else { continue; };
+                let i = self.index(c + d);
+                if g + step < cost[i] {
+                    cost[i] = g + step;
+                    open.push(Reverse((g + step, c.x + d.x, c.y + d.y)));
+                }
+            }
+        }
+
+        // Each cell points along its cheapest step. Blocked cells (a body shoved into a
+        // wall) step to the cheapest walkable neighbour, ignoring the corner rule, to get out.
+        for y in 0..self.rows {
+            for x in 0..self.cols {
+                let c = IVec2::new(x, y);
+                if c == goal { continue; }
+                let blocked = self.is_blocked(c);
+
+                let best = NEIGHBOURS
+                    .into_iter()
+                    .filter_map(|d| {
+                        let step = if blocked {
+                            (!self.is_blocked(c + d)).then_some(STRAIGHT)
+                        } else {
+                            self.step_cost(c, d, Some(goal))
+                        }?;
+                        let g = cost[self.index(c + d)];
+                        (g != u32::MAX).then_some((g + step, d))
+                    })
+                    .min_by_key(|&(g, _)| g);
+
+                let i = self.index(c);
+                field.dirs[i] = best.map(|(_, d)| d.as_vec2().normalize());
+            }
+        }
+        field
+    }
+}
+
+/// Per-cell walking direction toward one goal, built by `NavGrid::flow_field`.
+#[derive(Debug, Clone, Default)]
+pub struct FlowField {
+    origin: Vec2,
+    cols: i32,
+    rows: i32,
+    goal: Option<IVec2>,
+    /// Unit direction out of each cell (row-major); `None` at the goal and where it is
+    /// unreachable.
+    dirs: Vec<Option<Vec2>>,
+}
+
+impl FlowField {
+    /// Goal cell, or `None` for an empty field.
+    #[inline]
+    pub fn goal(&self) -> Option<IVec2> {
+        self.goal
+    }
+
+    /// Direction to walk from `p`: `None` off the field, in the goal cell, or where the
+    /// goal is unreachable (callers steer straight at the goal there).
+    #[inline]
+    pub fn direction_at(&self, p: Vec2) -> Option<Vec2> {
+        let c = ((p - self.origin) / NAV_CELL).floor().as_ivec2();
+        if c.x < 0 || c.y < 0 || c.x >= self.cols || c.y >= self.rows { return None; }
+        self.dirs[(c.y * self.cols + c.x) as usize]
+    }
+}
+
+/// True for colliders the grid treats as obstacles: static bodies on `Layer::World`.
+#[inline]
+fn is_obstacle(body: &RigidBody, layers: &CollisionLayers) -> bool {
+    matches!(body, RigidBody::Static) && layers.memberships.has_all(Layer::World)
+}
+
+pub fn reset_nav_grid(mut grid: ResMut<NavGrid>) {
+    grid.clear();
+}
+
+/// Colliders that appeared, changed shape or moved since the last sync.
+type ChangedColliders<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Collider, &'static Transform, &'static RigidBody, &'static CollisionLayers),
+    Or<(Added<Collider>, Changed<Collider>, Changed<Transform>)>,
+>;
+
+/// Fold added, moved and removed obstacles into the grid.
+///
+/// Reads `Transform` rather than `Position` so walls count from the tick they spawn,
+/// before the physics step has synced them.
+pub fn sync_nav_grid(
+    mut grid: ResMut<NavGrid>,
+    q_changed: ChangedColliders,
+    mut removed: RemovedComponents<Collider>,
+) {
+    for e in removed.read() {
+        grid.remove_obstacle(e);
+    }
+
+    for (e, collider, tf, body, layers) in &q_changed {
+        if !is_obstacle(body, layers) {
+            grid.remove_obstacle(e);
+            continue;
+        }
+        let angle = tf.rotation.to_euler(EulerRot::ZYX).0;
+        let aabb = collider.aabb(tf.translation.truncate(), Rotation::radians(angle));
+        grid.set_obstacle(e, Rect::from_corners(aabb.min, aabb.max));
+    }
+}
@@ -1,7 +1,10 @@
 use crate::common::test_utils::run_system_once;
+use crate::plugins::projectiles::layers::Layer;
 use avian2d::prelude::*;
 use bevy::prelude::*;
 
+use super::nav::{sync_nav_grid, NavGrid, NAV_CELL};
+
 #[test]
 fn spawns_walls_on_enter() {
     let mut world = World::new();
@@ -14,3 +17,149 @@ fn spawns_walls_on_enter() {
         .count();
     assert_eq!(walls, 4);
 }
+
+#[test]
+fn arena_bounds_cover_interior_with_margin() {
+    let bounds = super::ArenaBounds::default();
+    let half = Vec2::new(super::HALF_W as f32, super::HALF_H as f32);
+
+    assert!(bounds.contains_with_margin(Vec2::ZERO, 0.0));
+    assert!(bounds.contains_with_margin(half, 0.0));
+    assert!(!bounds.contains_with_margin(half + Vec2::new(10.0, 0.0), 0.0));
+    assert!(bounds.contains_with_margin(half + Vec2::new(10.0, 0.0), 32.0));
+}
+
+// -----------------------------------------------------------------------------
+// Navigation grid
+// -----------------------------------------------------------------------------
+
+#[test]
+fn arena_walls_leave_the_whole_grid_walkable() {
+    let mut world = World::new();
+    world.init_resource::<NavGrid>();
+    run_system_once(&mut world, super::spawn_arena);
+    run_system_once(&mut world, sync_nav_grid);
+
+    let grid = world.resource::<NavGrid>();
+    assert_eq!(grid.size(), IVec2::new(2 * super::HALF_W, 2 * super::HALF_H) / super::TILE);
+    for y in 0..grid.size().y {
+        for x in 0..grid.size().x {
+            assert!(!grid.is_blocked(IVec2::new(x, y)), "cell ({x}, {y}) blocked");
+        }
+    }
+}
+
+fn pillar(world: &mut World, pos: Vec2, size: Vec2) -> Entity {
+    world
+        .spawn((
+            Transform::from_translation(pos.extend(0.0)),
+            RigidBody::Static,
+            Collider::rectangle(size.x, size.y),
+            CollisionLayers::new(Layer::World, [Layer::Enemy]),
+        ))
+        .id()
+}
+
+#[test]
+fn obstacles_block_cells_incrementally() {
+    let mut world = World::new();
+    world.init_resource::<NavGrid>();
+
+    // Exactly one cell: edges that only touch a neighbour don't block it.
+    let at = Vec2::splat(NAV_CELL * 1.5);
+    let e = pillar(&mut world, at, Vec2::splat(NAV_CELL));
+    // Moving bodies are not obstacles.
+    world.spawn((
+        Transform::default(),
+        RigidBody::Dynamic,
+        Collider::circle(16.0),
+        CollisionLayers::new(Layer::Enemy, [Layer::World]),
+    ));
+    run_system_once(&mut world, sync_nav_grid);
+
+    let grid = world.resource::<NavGrid>();
+    let cell = grid.cell_at(at).unwrap();
+    assert!(grid.is_blocked(cell));
+    assert!(!grid.is_blocked(cell - IVec2::ONE));
+    let version = grid.version();
+
+    // Moving the obstacle frees its old cell; despawning it frees the new one.
+    world.get_mut::<Transform>(e).unwrap().translation.x += NAV_CELL;
+    run_system_once(&mut world, sync_nav_grid);
+    let grid = world.resource::<NavGrid>();
+    assert!(!grid.is_blocked(cell));
+    assert!(grid.is_blocked(cell + IVec2::X));
+    assert!(grid.version() > version);
+
+    world.despawn(e);
+    run_system_once(&mut world, sync_nav_grid);
+    assert!(!world.resource::<NavGrid>().is_blocked(cell + IVec2::X));
+}
+
+#[test]
+fn find_path_routes_around_obstacles_without_cutting_corners() {
+    let mut grid = NavGrid::new(Rect::new(0.0, 0.0, 5.0 * NAV_CELL, 5.0 * NAV_CELL), NAV_CELL);
+    // A wall along x = 2, open only at the top row.
+    grid.set_obstacle(Entity::PLACEHOLDER, Rect::new(2.0 * NAV_CELL, 0.0, 3.0 * NAV_CELL, 4.0 * NAV_CELL));
+    let center = |x: i32, y: i32| grid.cell_center(IVec2::new(x, y));
+
+    let (from, to) = (center(0, 0), center(4, 0));
+    let path = grid.find_path(from, to).expect("path through the gap");
+    assert_eq!(path.last(), Some(&to));
+
+    // Every leg between waypoints stays on walkable cells (the gap is on row 4).
+    let mut at = from;
+    for &next in &path {
+        for i in 0..=16 {
+            let p = at.lerp(next, i as f32 / 16.0);
+            assert!(!grid.is_blocked(grid.cell_at(p).unwrap()), "leg {at} -> {next} crosses the wall at {p}");
+        }
+        at = next;
+    }
+    assert!(path.iter().any(|p| grid.cell_at(*p).unwrap().y == 4));
+
+    // Straight line: only the goal.
+    assert_eq!(grid.find_path(from, center(0, 3)), Some(vec![center(0, 3)]));
+}
+
+#[test]
+fn find_path_reports_unreachable_goals() {
+    let mut grid = NavGrid::new(Rect::new(0.0, 0.0, 5.0 * NAV_CELL, 5.0 * NAV_CELL), NAV_CELL);
+    grid.set_obstacle(Entity::PLACEHOLDER, Rect::new(2.0 * NAV_CELL, 0.0, 3.0 * NAV_CELL, 5.0 * NAV_CELL));
+
+    let from = grid.cell_center(IVec2::new(0, 2));
+    assert_eq!(grid.find_path(from, grid.cell_center(IVec2::new(4, 2))), None);
+    assert_eq!(grid.find_path(from, Vec2::new(-10.0, 0.0)), None);
+}
+
+#[test]
+fn flow_field_leads_every_reachable_cell_to_the_goal() {
+    let mut grid = NavGrid::new(Rect::new(0.0, 0.0, 6.0 * NAV_CELL, 6.0 * NAV_CELL), NAV_CELL);
+    // A wall with the goal behind it.
+    grid.set_obstacle(Entity::PLACEHOLDER, Rect::new(2.0 * NAV_CELL, NAV_CELL, 3.0 * NAV_CELL, 5.0 * NAV_CELL));
+    let goal = grid.cell_center(IVec2::new(4, 3));
+    let field = grid.flow_field(goal);
+
+    assert_eq!(field.goal(), grid.cell_at(goal));
+    assert_eq!(field.direction_at(goal), None);
+    assert_eq!(field.direction_at(Vec2::new(-1.0, 0.0)), None);
+
+    // Walking the field from any walkable cell reaches the goal without entering a wall.
+    for y in 0..6 {
+        for x in 0..6 {
+            let mut c = IVec2::new(x, y);
+            if grid.is_blocked(c) { continue; }
+            for _ in 0..36 {
+                let Some(dir) = field.direction_at(grid.cell_center(c)) else { break; };
+                c += dir.round().as_ivec2();
+                assert!(!grid.is_blocked(c), "stepped into a wall at {c}");
+            }
+            assert_eq!(c, IVec2::new(4, 3), "from ({x}, {y})");
+        }
+    }
+
+    // A cell inside the wall points back out.
+    let inside = IVec2::new(2, 3);
+    let out = field.direction_at(grid.cell_center(inside)).expect("blocked cells lead out");
+    assert!(!grid.is_blocked(inside + out.round().as_ivec2()));
+}
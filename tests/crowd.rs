@@ -0,0 +1,135 @@
+//! Crowd benchmark: 1000 swarming enemies on the real movement systems, headless.
+//!
+//! The physics step is replaced by a plain `position += velocity * dt`, so this measures
+//! (and checks) only the crowd systems: flow field, separation, movement.
+//!
+//! The timing check is `#[ignore]`d like other perf gates (see `tests/README.md`):
+//!
+//! ```text
+//! cargo test --release --test crowd -- --ignored --nocapture
+//! ```
+
+use std::time::{Duration, Instant};
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use bevy_game::common::rng::SeededRng;
+use bevy_game::plugins::enemies::archetype::{spawn_enemy, EnemyArchetype, EnemyArchetypes};
+use bevy_game::plugins::enemies::crowd::{compute_separation, update_crowd_flow, CrowdFlow, SEPARATION_RADIUS};
+use bevy_game::plugins::enemies::movement::{apply_enemy_movement, update_nav_paths, Movement};
+use bevy_game::plugins::enemies::EnemyLifeState;
+use bevy_game::plugins::projectiles::components::{Enemy, PlayerEntity};
+use bevy_game::plugins::world::nav::NavGrid;
+
+const AGENTS: usize = 1000;
+const DT: f32 = 1.0 / 64.0;
+
+/// Stand-in for the physics step.
+fn integrate(time: Res<Time<Fixed>>, mut q: Query<(&mut Position, &LinearVelocity)>) {
+    for (mut pos, vel) in &mut q {
+        pos.0 += vel.0 * time.delta_secs();
+    }
+}
+
+/// 1000 swarmers scattered over the arena (none near the player), two pillars in the way.
+fn horde() -> (World, Schedule) {
+    let mut world = World::new();
+    let mut fixed = Time::<Fixed>::default();
+    fixed.advance_by(Duration::from_secs_f32(DT));
+    world.insert_resource(fixed);
+    world.init_resource::<CrowdFlow>();
+
+    let mut grid = NavGrid::default();
+    for x in [-320.0, 320.0] {
+        let pillar = world.spawn_empty().id();
+        grid.set_obstacle(pillar, Rect::from_center_half_size(Vec2::new(x, 0.0), Vec2::new(64.0, 128.0)));
+    }
+    world.insert_resource(grid);
+
+    let player = world.spawn(Position(Vec2::ZERO)).id();
+    world.insert_resource(PlayerEntity(Some(player)));
+
+    let mut archetypes = EnemyArchetypes::default();
+    archetypes.insert(
+        "swarmer",
+        EnemyArchetype { movement: Movement::Swarm { speed: 90.0 }, ..default() },
+        None,
+        None,
+    );
+
+    let mut rng = SeededRng::new(1000);
+    let mut spawned = Vec::with_capacity(AGENTS);
+    {
+        let mut commands = world.commands();
+        while spawned.len() < AGENTS {
+            let pos = Vec2::new(rng.next_f32() * 2000.0 - 1000.0, rng.next_f32() * 1100.0 - 550.0);
+            if pos.length() < 250.0 { continue; }
+            let e = spawn_enemy(&mut commands, &archetypes, "swarmer", pos).expect("archetype inserted");
+            spawned.push((e, pos));
+        }
+    }
+    world.flush();
+    // No physics plugin to sync `Position` from `Transform` or add velocities.
+    for (e, pos) in spawned {
+        world.entity_mut(e).insert((Position(pos), LinearVelocity::ZERO));
+    }
+
+    let mut schedule = Schedule::new(FixedPostUpdate);
+    schedule.add_systems(
+        (update_crowd_flow, compute_separation, update_nav_paths, apply_enemy_movement, integrate).chain(),
+    );
+    (world, schedule)
+}
+
+fn agent_positions(world: &mut World) -> Vec<Vec2> {
+    world
+        .query_filtered::<(&Position, &EnemyLifeState), With<Enemy>>()
+        .iter(world)
+        .filter(|(_, life)| matches!(life, EnemyLifeState::Alive))
+        .map(|(pos, _)| pos.0)
+        .collect()
+}
+
+#[test]
+fn horde_converges_on_the_player_without_stacking() {
+    let (mut world, mut schedule) = horde();
+    assert_eq!(agent_positions(&mut world).len(), AGENTS);
+
+    for _ in 0..64 * 10 {
+        schedule.run(&mut world);
+    }
+
+    let positions = agent_positions(&mut world);
+    let near = positions.iter().filter(|p| p.length() < 200.0).count();
+    assert!(near > 50, "only {near} agents reached the player");
+
+    let mut closest = f32::MAX;
+    for (i, a) in positions.iter().enumerate() {
+        for b in &positions[i + 1..] {
+            closest = closest.min(a.distance(*b));
+        }
+    }
+    assert!(closest > SEPARATION_RADIUS * 0.1, "agents stacked: closest pair {closest} px apart");
+}
+
+#[test]
+#[ignore]
+fn horde_of_1000_fits_the_fixed_step_budget() {
+    // The crowd may use a quarter of the step; physics and everything else get the rest.
+    let budget = Duration::from_secs_f32(DT / 4.0);
+    const TICKS: u32 = 640;
+
+    let (mut world, mut schedule) = horde();
+    // Warm up: first flow field, buffer growth.
+    schedule.run(&mut world);
+
+    let started = Instant::now();
+    for _ in 0..TICKS {
+        schedule.run(&mut world);
+    }
+    let per_tick = started.elapsed() / TICKS;
+
+    println!("crowd: {AGENTS} agents, {per_tick:?} per tick (budget {budget:?})");
+    assert!(per_tick < budget, "crowd systems took {per_tick:?} per tick, budget {budget:?}");
+}
@@ -46,3 +46,19 @@ fn player_interpolation_pipeline_is_wired() {
     );
 
 }
+
+#[test]
+fn run_stats_start_fresh_in_game() {
+    let mut app = common::app_headless();
+
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::InGame);
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let stats = app.world().resource::<bevy_game::plugins::score::RunStats>();
+    assert_eq!(stats.kills, 0);
+    assert_eq!(stats.combo.multiplier, 1.0);
+}